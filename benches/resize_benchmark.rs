@@ -1,14 +1,110 @@
-use criterion::{criterion_group, criterion_main, Criterion};
-use fastresize::{ProcessingEngine, ResizeConfig, ResizeMode};
-use std::path::Path;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fastresize::config::FilterType;
+use fastresize::processing::ImageResizer;
+use fastresize::{ImageFormat, ProcessingEngine, ResizeConfig, ResizeMode};
+use image::{DynamicImage, Rgb, RgbImage};
 
-// Note: This benchmark requires test images to be present
-// For now, it's a placeholder implementation
+// Inputs are generated in memory so no fixture files are needed
+const WIDTH: u32 = 1920;
+const HEIGHT: u32 = 1080;
 
-fn benchmark_resize(_c: &mut Criterion) {
-    // TODO: Implement benchmarking once we have test images
-    // This would typically test resize performance with different algorithms and sizes
+/// Gradient with a checkerboard overlay, so filters see both flat and sharp regions
+fn test_image(width: u32, height: u32) -> DynamicImage {
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        let checker = if (x / 32 + y / 32) % 2 == 0 { 64 } else { 0 };
+        Rgb([
+            ((x * 255 / width) as u8).saturating_add(checker),
+            ((y * 255 / height) as u8).saturating_add(checker),
+            (((x + y) * 255 / (width + height)) as u8),
+        ])
+    });
+    DynamicImage::ImageRgb8(image)
 }
 
-criterion_group!(benches, benchmark_resize);
-criterion_main!(benches);
\ No newline at end of file
+fn benchmark_resize_modes(c: &mut Criterion) {
+    let image = test_image(WIDTH, HEIGHT);
+    let resizer = ImageResizer::new();
+
+    let modes = [
+        ("scale", ResizeMode::Scale { factor: 0.5 }),
+        ("width", ResizeMode::Width { width: 800 }),
+        ("height", ResizeMode::Height { height: 600 }),
+        ("fit", ResizeMode::Fit { width: 800, height: 800 }),
+        ("fill", ResizeMode::fill(800, 800)),
+    ];
+
+    let mut group = c.benchmark_group("resize_mode");
+    group.throughput(Throughput::Elements(u64::from(WIDTH * HEIGHT)));
+    for (name, mode) in &modes {
+        group.bench_with_input(BenchmarkId::from_parameter(name), mode, |b, mode| {
+            b.iter(|| resizer.resize(black_box(&image), mode).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_resize_filters(c: &mut Criterion) {
+    let image = test_image(WIDTH, HEIGHT);
+    let mode = ResizeMode::Fit { width: 800, height: 800 };
+
+    let mut group = c.benchmark_group("resize_filter");
+    group.throughput(Throughput::Elements(u64::from(WIDTH * HEIGHT)));
+    for &filter in FilterType::all() {
+        let resizer = ImageResizer::with_filter(filter);
+        group.bench_function(BenchmarkId::from_parameter(filter.name()), |b| {
+            b.iter(|| resizer.resize(black_box(&image), &mode).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_smart_crop(c: &mut Criterion) {
+    let image = test_image(WIDTH, HEIGHT);
+    let resizer = ImageResizer::new();
+
+    let focus_points = [("center", None), ("focus", Some((0.25, 0.75)))];
+
+    let mut group = c.benchmark_group("smart_crop");
+    for (name, focus) in focus_points {
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                resizer
+                    .resize_with_smart_crop(black_box(&image), 600, 600, focus)
+                    .unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_save_image(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let engine = ProcessingEngine::new();
+    let image = test_image(800, 600);
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    let mut group = c.benchmark_group("save_image");
+    group.throughput(Throughput::Elements(800 * 600));
+    for &format in ImageFormat::all() {
+        let config = ResizeConfig::new().format(format).quality(85);
+        let output_path = temp_dir.path().join(format!("output.{}", format.extension()));
+
+        group.bench_function(BenchmarkId::from_parameter(format.extension()), |b| {
+            b.iter(|| {
+                runtime
+                    .block_on(engine.save_image(black_box(&image), &output_path, &config))
+                    .unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_resize_modes,
+    benchmark_resize_filters,
+    benchmark_smart_crop,
+    benchmark_save_image
+);
+criterion_main!(benches);
@@ -1,6 +1,6 @@
 //! Basic usage example for the FastResize library
 
-use fastresize::{init, ProcessingEngine, ResizeConfig, ResizeMode};
+use fastresize::{init, ProcessingEngine, ResizeConfig};
 use std::path::Path;
 
 #[tokio::main]
@@ -1,6 +1,6 @@
 //! Batch processing example with parallel execution
 
-use fastresize::{init, parallel::ParallelProcessor, ResizeConfig, ResizeMode};
+use fastresize::{init, parallel::ParallelProcessor, ResizeConfig};
 use std::path::PathBuf;
 
 #[tokio::main]
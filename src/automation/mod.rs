@@ -0,0 +1,5 @@
+//! Automation features for unattended processing workflows
+
+pub mod watch;
+
+pub use watch::*;
@@ -0,0 +1,445 @@
+//! File system watching for automatic processing of new images
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::config::{Config, ProcessingProfile};
+use crate::error::{Result, FastResizeError, ErrorContext};
+use crate::processing::ProcessingEngine;
+use crate::processing::formats::is_supported_input_format;
+
+/// Shortest interval between checks of pending files
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A watched directory and the profile applied to new images in it
+#[derive(Debug, Clone)]
+pub struct WatchTarget {
+    /// Directory to watch
+    pub path: PathBuf,
+
+    /// Output directory for processed images
+    pub output: PathBuf,
+
+    /// Processing profile applied to each new image
+    pub profile: ProcessingProfile,
+
+    /// Watch subdirectories recursively
+    pub recursive: bool,
+}
+
+impl WatchTarget {
+    /// Create a new watch target
+    pub fn new(path: PathBuf, output: PathBuf, profile: ProcessingProfile, recursive: bool) -> Self {
+        Self {
+            path,
+            output,
+            profile,
+            recursive,
+        }
+    }
+
+    /// Check whether a file lies inside this target
+    pub fn contains(&self, file: &Path) -> bool {
+        if self.recursive {
+            file.starts_with(&self.path)
+        } else {
+            file.parent() == Some(self.path.as_path())
+        }
+    }
+
+    /// Output directory for a file, mirroring its location below the watched folder
+    pub fn output_dir_for(&self, file: &Path) -> PathBuf {
+        let relative = file
+            .parent()
+            .and_then(|parent| parent.strip_prefix(&self.path).ok())
+            .unwrap_or_else(|| Path::new(""));
+        self.output.join(relative)
+    }
+}
+
+/// Watches folders and processes images once they have been fully written
+pub struct FileWatcher {
+    engine: Arc<ProcessingEngine>,
+    targets: Vec<WatchTarget>,
+    debounce: Duration,
+}
+
+impl FileWatcher {
+    /// Create a watcher that waits `debounce` after the last event before checking a file
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            engine: Arc::new(ProcessingEngine::new()),
+            targets: Vec::new(),
+            debounce,
+        }
+    }
+
+    /// Create a watcher for all watch folders in the automation configuration
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut watcher = Self::new(Duration::from_millis(config.automation.watch_interval));
+
+        for folder in &config.automation.watch_folders {
+            let profile = config.get_profile(&folder.profile)?.clone();
+            watcher.add_target(WatchTarget::new(
+                folder.path.clone(),
+                folder.output.clone(),
+                profile,
+                folder.recursive,
+            ));
+        }
+
+        Ok(watcher)
+    }
+
+    /// Add a folder to watch
+    pub fn add_target(&mut self, target: WatchTarget) {
+        self.targets.push(target);
+    }
+
+    /// Get the configured watch targets
+    pub fn targets(&self) -> &[WatchTarget] {
+        &self.targets
+    }
+
+    /// Watch until Ctrl+C is received
+    pub async fn run(self) -> Result<()> {
+        self.run_until(async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                warn!("Failed to listen for Ctrl+C: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }).await
+    }
+
+    /// Watch until the given shutdown future completes
+    pub async fn run_until<F: Future<Output = ()>>(mut self, shutdown: F) -> Result<()> {
+        if self.targets.is_empty() {
+            return Err(FastResizeError::config("No folders to watch"));
+        }
+
+        self.prepare_targets().await?;
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let _ = sender.send(event);
+        })?;
+
+        for (path, mode) in self.watch_paths() {
+            info!("Watching {:?} ({:?})", path, mode);
+            watcher.watch(&path, mode)?;
+        }
+
+        let mut pending = PendingFiles::default();
+        let mut produced = HashSet::new();
+        let mut ticker = tokio::time::interval((self.debounce / 2).max(MIN_POLL_INTERVAL));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        tokio::pin!(shutdown);
+
+        loop {
+            tokio::select! {
+                () = &mut shutdown => {
+                    info!("Stopping watch mode");
+                    break;
+                }
+                Some(event) = receiver.recv() => match event {
+                    Ok(event) => self.handle_event(event, &mut pending, &produced),
+                    Err(e) => warn!("File watcher error: {}", e),
+                },
+                _ = ticker.tick() => {
+                    let ready = pending.take_ready(Instant::now(), self.debounce, file_size);
+                    for path in ready {
+                        self.process_ready(&path, &mut produced).await;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate watched folders and resolve paths so they match event paths
+    async fn prepare_targets(&mut self) -> Result<()> {
+        for target in &mut self.targets {
+            if !target.path.is_dir() {
+                return Err(FastResizeError::config(
+                    format!("Watch folder is not a directory: {}", target.path.display())
+                ));
+            }
+            target.profile.validate()?;
+
+            tokio::fs::create_dir_all(&target.output).await
+                .with_file_context(target.output.clone())?;
+
+            target.path = tokio::fs::canonicalize(&target.path).await?;
+            target.output = tokio::fs::canonicalize(&target.output).await?;
+        }
+        Ok(())
+    }
+
+    /// Unique directories to register with the OS watcher
+    fn watch_paths(&self) -> Vec<(PathBuf, RecursiveMode)> {
+        let mut paths: HashMap<PathBuf, bool> = HashMap::new();
+        for target in &self.targets {
+            *paths.entry(target.path.clone()).or_default() |= target.recursive;
+        }
+
+        paths.into_iter()
+            .map(|(path, recursive)| {
+                let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+                (path, mode)
+            })
+            .collect()
+    }
+
+    /// Record file system events for candidate images
+    fn handle_event(&self, event: Event, pending: &mut PendingFiles, produced: &HashSet<PathBuf>) {
+        match event.kind {
+            EventKind::Create(_) | EventKind::Modify(_) => {
+                let now = Instant::now();
+                for path in event.paths {
+                    if !produced.contains(&path) && self.is_candidate(&path) {
+                        debug!("Detected change: {:?}", path);
+                        pending.touch(path, now);
+                    }
+                }
+            }
+            EventKind::Remove(_) => {
+                for path in &event.paths {
+                    pending.remove(path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Check whether a path is a supported image inside a watched folder
+    fn is_candidate(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(is_supported_input_format)
+            && self.targets.iter().any(|target| target.contains(path))
+    }
+
+    /// Process a stable file with every target that contains it
+    async fn process_ready(&self, path: &Path, produced: &mut HashSet<PathBuf>) {
+        for target in self.targets.iter().filter(|target| target.contains(path)) {
+            let output_dir = target.output_dir_for(path);
+
+            match self.engine.process_file_with_profile(path, &output_dir, &target.profile).await {
+                Ok(result) => {
+                    info!("Processed {} -> {}", path.display(), result.output_path.display());
+                    produced.insert(result.output_path);
+                }
+                Err(e) => {
+                    warn!("Failed to process {}: {}", path.display(), e.user_message());
+                }
+            }
+        }
+    }
+}
+
+/// Current size of a file, if it still exists
+fn file_size(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|metadata| metadata.len())
+}
+
+/// Files with recent events that are waiting to stop changing
+#[derive(Debug, Default)]
+struct PendingFiles {
+    files: HashMap<PathBuf, PendingFile>,
+}
+
+#[derive(Debug)]
+struct PendingFile {
+    last_event: Instant,
+    last_size: Option<u64>,
+}
+
+impl PendingFiles {
+    /// Record an event for a file
+    fn touch(&mut self, path: PathBuf, now: Instant) {
+        self.files
+            .entry(path)
+            .and_modify(|file| file.last_event = now)
+            .or_insert(PendingFile { last_event: now, last_size: None });
+    }
+
+    /// Stop tracking a file
+    fn remove(&mut self, path: &Path) {
+        self.files.remove(path);
+    }
+
+    /// Take files that have been quiet for `debounce` and kept the same size
+    /// since the previous check
+    fn take_ready<F: Fn(&Path) -> Option<u64>>(
+        &mut self,
+        now: Instant,
+        debounce: Duration,
+        size_of: F,
+    ) -> Vec<PathBuf> {
+        let mut ready = Vec::new();
+
+        self.files.retain(|path, file| {
+            if now.duration_since(file.last_event) < debounce {
+                return true;
+            }
+
+            match size_of(path) {
+                // File disappeared before it settled
+                None => false,
+                Some(size) if size > 0 && file.last_size == Some(size) => {
+                    ready.push(path.clone());
+                    false
+                }
+                Some(size) => {
+                    file.last_size = Some(size);
+                    file.last_event = now;
+                    true
+                }
+            }
+        });
+
+        ready.sort();
+        ready
+    }
+
+    /// Number of files being tracked
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.files.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+    use crate::config::WatchFolder;
+
+    #[test]
+    fn test_target_contains() {
+        let flat = WatchTarget::new(
+            PathBuf::from("/in"),
+            PathBuf::from("/out"),
+            ProcessingProfile::width(100),
+            false,
+        );
+        assert!(flat.contains(Path::new("/in/a.jpg")));
+        assert!(!flat.contains(Path::new("/in/sub/a.jpg")));
+        assert!(!flat.contains(Path::new("/other/a.jpg")));
+
+        let recursive = WatchTarget { recursive: true, ..flat };
+        assert!(recursive.contains(Path::new("/in/sub/a.jpg")));
+        assert_eq!(
+            recursive.output_dir_for(Path::new("/in/sub/a.jpg")),
+            PathBuf::from("/out/sub")
+        );
+        assert_eq!(
+            recursive.output_dir_for(Path::new("/in/a.jpg")),
+            PathBuf::from("/out")
+        );
+    }
+
+    #[test]
+    fn test_pending_files_wait_for_stable_size() {
+        let mut pending = PendingFiles::default();
+        let debounce = Duration::from_millis(100);
+        let start = Instant::now();
+        let size = Cell::new(10);
+
+        pending.touch(PathBuf::from("a.jpg"), start);
+
+        // Still inside the debounce window
+        let ready = pending.take_ready(start + Duration::from_millis(50), debounce, |_| Some(size.get()));
+        assert!(ready.is_empty());
+
+        // First check records the size
+        let ready = pending.take_ready(start + debounce, debounce, |_| Some(size.get()));
+        assert!(ready.is_empty());
+
+        // File grew, so it is not ready yet
+        size.set(20);
+        let ready = pending.take_ready(start + debounce * 2, debounce, |_| Some(size.get()));
+        assert!(ready.is_empty());
+
+        // Same size after another quiet period
+        let ready = pending.take_ready(start + debounce * 3, debounce, |_| Some(size.get()));
+        assert_eq!(ready, vec![PathBuf::from("a.jpg")]);
+        assert_eq!(pending.len(), 0);
+    }
+
+    #[test]
+    fn test_pending_files_drop_missing() {
+        let mut pending = PendingFiles::default();
+        let start = Instant::now();
+
+        pending.touch(PathBuf::from("gone.jpg"), start);
+        let ready = pending.take_ready(start + Duration::from_secs(1), Duration::from_millis(10), |_| None);
+        assert!(ready.is_empty());
+        assert_eq!(pending.len(), 0);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = Config::default();
+        config.automation.watch_folders.push(WatchFolder {
+            path: PathBuf::from("./uploads"),
+            profile: "web".to_string(),
+            output: PathBuf::from("./web"),
+            recursive: true,
+        });
+
+        let watcher = FileWatcher::from_config(&config).unwrap();
+        assert_eq!(watcher.targets().len(), 1);
+        assert_eq!(watcher.debounce, Duration::from_millis(config.automation.watch_interval));
+
+        config.automation.watch_folders[0].profile = "missing".to_string();
+        assert!(FileWatcher::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_processes_new_file() {
+        let input = TempDir::new().unwrap();
+        let output = TempDir::new().unwrap();
+
+        let mut watcher = FileWatcher::new(Duration::from_millis(50));
+        watcher.add_target(WatchTarget::new(
+            input.path().to_path_buf(),
+            output.path().to_path_buf(),
+            ProcessingProfile::width(8),
+            false,
+        ));
+
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let handle = tokio::spawn(watcher.run_until(async {
+            let _ = stopped.await;
+        }));
+
+        // Give the OS watcher time to register
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let image = image::RgbImage::from_pixel(16, 16, image::Rgb([200, 100, 50]));
+        image.save(input.path().join("photo.png")).unwrap();
+
+        let expected = output.path().join("photo_resized.png");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !expected.exists() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        let _ = stop.send(());
+        handle.await.unwrap().unwrap();
+
+        let resized = image::open(&expected).unwrap();
+        assert_eq!(resized.width(), 8);
+    }
+}
@@ -1,7 +1,9 @@
 //! Configuration management for FastResize
 
 use std::collections::HashMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 use crate::error::{Result, FastResizeError};
 
@@ -33,7 +35,10 @@ impl Default for Config {
             resize_mode: ResizeMode::Width { width: 1920 },
             quality: 85,
             format: Some(ImageFormat::WebP),
-            naming: NamingConfig::default(),
+            metadata: MetadataPolicy::CopyrightOnly,
+            color_space: ColorSpace::Srgb,
+            upscale: UpscalePolicy::Never,
+            ..Default::default()
         });
         
         // Default thumbnail profile  
@@ -41,10 +46,15 @@ impl Default for Config {
             resize_mode: ResizeMode::Fit { width: 300, height: 300 },
             quality: 80,
             format: Some(ImageFormat::WebP),
+            metadata: MetadataPolicy::CopyrightOnly,
+            color_space: ColorSpace::Srgb,
+            upscale: UpscalePolicy::Never,
+            sharpen: Some(SharpenOptions::default()),
             naming: NamingConfig {
                 suffix: Some("_thumb".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         });
 
         Self {
@@ -185,13 +195,316 @@ pub enum ResizeMode {
     #[serde(rename = "height")]
     Height { height: u32 },
     
-    /// Fit within dimensions (letterbox/pillarbox)
+    /// Resize the longer side to `px`, maintain aspect ratio
+    #[serde(rename = "long_edge")]
+    LongEdge { px: u32 },
+    
+    /// Resize the shorter side to `px`, maintain aspect ratio
+    #[serde(rename = "short_edge")]
+    ShortEdge { px: u32 },
+    
+    /// Shrink until width x height fits a budget in megapixels, maintain aspect ratio
+    #[serde(rename = "max_pixels")]
+    MaxPixels { megapixels: f32 },
+    
+    /// Fit within dimensions, without padding
     #[serde(rename = "fit")]
     Fit { width: u32, height: u32 },
     
-    /// Fill dimensions (crop if necessary)
+    /// Fit within dimensions, then pad to exactly that size (letterbox/pillarbox)
+    #[serde(rename = "pad")]
+    Pad {
+        width: u32,
+        height: u32,
+        /// Color of the padding
+        #[serde(default)]
+        background: Background,
+        /// Where the image sits on the canvas
+        #[serde(default)]
+        anchor: CropAnchor,
+    },
+    
+    /// Fit within a print size at `dpi`, maintain aspect ratio; the output
+    /// is tagged with the resolution
+    #[serde(rename = "physical")]
+    Physical {
+        width: f32,
+        height: f32,
+        unit: LengthUnit,
+        dpi: f32,
+    },
+    
+    /// Fill dimensions exactly (crop if necessary)
     #[serde(rename = "fill")]
-    Fill { width: u32, height: u32 },
+    Fill {
+        width: u32,
+        height: u32,
+        /// Part of the image kept when cropping
+        #[serde(default)]
+        anchor: CropAnchor,
+        /// How the crop position is chosen
+        #[serde(default)]
+        crop: CropStrategy,
+    },
+}
+
+impl ResizeMode {
+    /// Fill dimensions exactly with a centered crop
+    pub fn fill(width: u32, height: u32) -> Self {
+        Self::Fill {
+            width,
+            height,
+            anchor: CropAnchor::default(),
+            crop: CropStrategy::default(),
+        }
+    }
+    
+    /// Pad to exact dimensions, centering the image on the background
+    pub fn pad(width: u32, height: u32, background: Background) -> Self {
+        Self::Pad {
+            width,
+            height,
+            background,
+            anchor: CropAnchor::default(),
+        }
+    }
+    
+    /// Resolution the output is tagged with, for physical sizes
+    pub fn dpi(&self) -> Option<f32> {
+        match self {
+            Self::Physical { dpi, .. } => Some(*dpi),
+            _ => None,
+        }
+    }
+}
+
+/// Unit of a physical print size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[serde(rename = "mm")]
+    Millimeter,
+    #[serde(rename = "in")]
+    Inch,
+}
+
+impl LengthUnit {
+    /// Pixels covering `length` of this unit at `dpi`
+    pub fn to_pixels(self, length: f32, dpi: f32) -> u32 {
+        let inches = match self {
+            Self::Millimeter => length / 25.4,
+            Self::Inch => length,
+        };
+        (inches * dpi).round() as u32
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = FastResizeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "mm" => Ok(Self::Millimeter),
+            "in" | "inch" | "inches" => Ok(Self::Inch),
+            other => Err(FastResizeError::invalid_parameters(
+                format!("Invalid length unit '{}', expected mm or in", other)
+            )),
+        }
+    }
+}
+
+/// Background color for padding
+///
+/// Written as `#rrggbb`, `#rgb` or `transparent`. Transparent padding
+/// becomes white in output formats without an alpha channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Background {
+    #[default]
+    Transparent,
+    Color([u8; 3]),
+}
+
+impl Background {
+    /// Opaque white
+    pub const WHITE: Self = Self::Color([255, 255, 255]);
+
+    /// Background as an RGBA pixel value
+    pub fn rgba(self) -> [u8; 4] {
+        match self {
+            Self::Transparent => [0, 0, 0, 0],
+            Self::Color([r, g, b]) => [r, g, b, 255],
+        }
+    }
+
+    /// Replace transparency with white when the format cannot store alpha
+    pub fn for_format(self, format: ImageFormat) -> Self {
+        match self {
+            Self::Transparent if !format.supports_alpha() => Self::WHITE,
+            background => background,
+        }
+    }
+}
+
+impl FromStr for Background {
+    type Err = FastResizeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        if s == "transparent" {
+            return Ok(Self::Transparent);
+        }
+
+        let invalid = || FastResizeError::invalid_parameters(
+            format!("Invalid background '{}', expected #rrggbb, #rgb or transparent", s)
+        );
+        let hex = s.strip_prefix('#').unwrap_or(&s);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+        match hex.len() {
+            6 => Ok(Self::Color([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?])),
+            // Each shorthand digit is doubled: #fa0 is #ffaa00
+            3 => Ok(Self::Color([
+                channel(&hex[0..1])? * 17,
+                channel(&hex[1..2])? * 17,
+                channel(&hex[2..3])? * 17,
+            ])),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<String> for Background {
+    type Error = FastResizeError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Background> for String {
+    fn from(background: Background) -> Self {
+        background.to_string()
+    }
+}
+
+impl fmt::Display for Background {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transparent => write!(f, "transparent"),
+            Self::Color([r, g, b]) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+/// Part of the image kept when cropping to fill
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CropAnchor {
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// How the crop position is chosen when filling
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum CropStrategy {
+    /// Keep the anchored part of the image
+    #[default]
+    Anchor,
+    /// Center the crop on a fixed point (fractions of width and height, 0.0-1.0)
+    Focus { x: f32, y: f32 },
+    /// Center the crop on the region with the most edge energy
+    Attention,
+    /// Read a per-file focus point from a JSON sidecar, falling back to the anchor
+    Sidecar,
+}
+
+impl CropAnchor {
+    /// Anchor position as fractions of width and height (0.0-1.0)
+    pub fn focus_point(self) -> (f32, f32) {
+        match self {
+            Self::Center => (0.5, 0.5),
+            Self::Top => (0.5, 0.0),
+            Self::Bottom => (0.5, 1.0),
+            Self::Left => (0.0, 0.5),
+            Self::Right => (1.0, 0.5),
+            Self::TopLeft => (0.0, 0.0),
+            Self::TopRight => (1.0, 0.0),
+            Self::BottomLeft => (0.0, 1.0),
+            Self::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// Available resize filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterType {
+    /// Nearest neighbor (fastest, lowest quality)
+    Nearest,
+    /// Triangle (linear interpolation)
+    Triangle,
+    /// Catmull-Rom cubic spline
+    CatmullRom,
+    /// Gaussian blur
+    Gaussian,
+    /// Lanczos with radius 3 (high quality, recommended)
+    Lanczos3,
+    /// Choose from the scale factor when resizing (see `FilterType::resolve`)
+    Auto,
+}
+
+impl Default for FilterType {
+    fn default() -> Self {
+        Self::Lanczos3
+    }
+}
+
+impl FilterType {
+    /// All concrete filters, fastest first
+    pub fn all() -> &'static [FilterType] {
+        &[Self::Nearest, Self::Triangle, Self::CatmullRom, Self::Gaussian, Self::Lanczos3]
+    }
+
+    /// Lowercase filter name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Nearest => "nearest",
+            Self::Triangle => "triangle",
+            Self::CatmullRom => "catmullrom",
+            Self::Gaussian => "gaussian",
+            Self::Lanczos3 => "lanczos3",
+            Self::Auto => "auto",
+        }
+    }
+}
+
+/// Output color space
+///
+/// Outputs that cannot embed an ICC profile (AVIF, GIF, BMP) always end up
+/// in sRGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorSpace {
+    /// Convert to sRGB and embed an sRGB profile
+    Srgb,
+    /// Convert to Adobe RGB (1998) and embed its profile
+    AdobeRgb,
+    /// Convert to Display P3 and embed its profile
+    DisplayP3,
+    /// Keep the pixels and embed the input's own profile
+    #[default]
+    Auto,
 }
 
 /// Supported image formats
@@ -204,9 +517,15 @@ pub enum ImageFormat {
     Gif,
     Tiff,
     Bmp,
+    Avif,
 }
 
 impl ImageFormat {
+    /// All supported output formats
+    pub fn all() -> &'static [ImageFormat] {
+        &[Self::Jpeg, Self::Png, Self::WebP, Self::Gif, Self::Tiff, Self::Bmp, Self::Avif]
+    }
+
     /// Get file extension for this format
     pub fn extension(self) -> &'static str {
         match self {
@@ -216,9 +535,20 @@ impl ImageFormat {
             Self::Gif => "gif",
             Self::Tiff => "tiff",
             Self::Bmp => "bmp",
+            Self::Avif => "avif",
         }
     }
 
+    /// Whether the format can store an alpha channel
+    pub fn supports_alpha(self) -> bool {
+        matches!(self, Self::Png | Self::WebP | Self::Gif | Self::Tiff | Self::Avif)
+    }
+
+    /// Whether images in this format can be decoded; AVIF is encode-only
+    pub fn can_decode(self) -> bool {
+        !matches!(self, Self::Avif)
+    }
+
     /// Get MIME type for this format
     pub fn mime_type(self) -> &'static str {
         match self {
@@ -228,6 +558,7 @@ impl ImageFormat {
             Self::Gif => "image/gif",
             Self::Tiff => "image/tiff",
             Self::Bmp => "image/bmp",
+            Self::Avif => "image/avif",
         }
     }
 }
@@ -398,6 +729,38 @@ mod tests {
         assert_eq!(ImageFormat::WebP.mime_type(), "image/webp");
     }
 
+    #[test]
+    fn test_background_parsing() {
+        assert_eq!("#FFFFFF".parse::<Background>().unwrap(), Background::WHITE);
+        assert_eq!("fa0".parse::<Background>().unwrap(), Background::Color([255, 170, 0]));
+        assert_eq!("transparent".parse::<Background>().unwrap(), Background::Transparent);
+        assert!("#ffff".parse::<Background>().is_err());
+        assert!("#gggggg".parse::<Background>().is_err());
+
+        assert_eq!(Background::Transparent.for_format(ImageFormat::Jpeg), Background::WHITE);
+        assert_eq!(Background::Transparent.for_format(ImageFormat::Png), Background::Transparent);
+
+        let mode: ResizeMode = toml::from_str("type = \"pad\"\nwidth = 800\nheight = 800\nbackground = \"#102030\"").unwrap();
+        assert!(matches!(
+            mode,
+            ResizeMode::Pad { width: 800, background: Background::Color([16, 32, 48]), anchor: CropAnchor::Center, .. }
+        ));
+        assert!(toml::to_string(&mode).unwrap().contains("background = \"#102030\""));
+    }
+
+    #[test]
+    fn test_physical_size() {
+        assert_eq!(LengthUnit::Inch.to_pixels(6.0, 300.0), 1800);
+        assert_eq!(LengthUnit::Millimeter.to_pixels(150.0, 300.0), 1772);
+        assert_eq!("in".parse::<LengthUnit>().unwrap(), LengthUnit::Inch);
+        assert_eq!("MM".parse::<LengthUnit>().unwrap(), LengthUnit::Millimeter);
+        assert!("cm".parse::<LengthUnit>().is_err());
+
+        let mode: ResizeMode = toml::from_str("type = \"physical\"\nwidth = 6\nheight = 4\nunit = \"in\"\ndpi = 300").unwrap();
+        assert_eq!(mode.dpi(), Some(300.0));
+        assert_eq!(ResizeMode::Width { width: 100 }.dpi(), None);
+    }
+
     #[test]
     fn test_profile_lookup() {
         let config = Config::default();
@@ -1,7 +1,8 @@
 //! Processing profiles for different use cases
 
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
-use crate::config::{ResizeMode, ImageFormat};
+use crate::config::{ColorSpace, CropAnchor, CropStrategy, FilterType, LengthUnit, ResizeMode, ImageFormat};
 use crate::error::{Result, FastResizeError};
 
 /// A processing profile defines how images should be resized
@@ -16,18 +17,91 @@ pub struct ProcessingProfile {
     /// Output format (None = keep original)
     pub format: Option<ImageFormat>,
     
+    /// Resampling filter
+    #[serde(default)]
+    pub filter: FilterType,
+    
+    /// WebP encoder options
+    #[serde(default)]
+    pub webp: WebPOptions,
+    
+    /// AVIF encoder options
+    #[serde(default)]
+    pub avif: AvifOptions,
+    
+    /// Keep only the first frame of animated inputs
+    #[serde(default)]
+    pub poster: bool,
+    
+    /// Turn images upright using their EXIF orientation before resizing
+    #[serde(default = "default_true")]
+    pub auto_orient: bool,
+    
+    /// Which EXIF, XMP and IPTC metadata is carried into the output
+    #[serde(default)]
+    pub metadata: MetadataPolicy,
+    
+    /// Output color space (ICC profile handling)
+    #[serde(default)]
+    pub color_space: ColorSpace,
+    
+    /// Resample in linear light instead of on gamma-encoded values
+    #[serde(default)]
+    pub linear_light: bool,
+    
+    /// How far images may be enlarged
+    #[serde(default)]
+    pub upscale: UpscalePolicy,
+    
+    /// Largest still-image output size in bytes; quality, then dimensions,
+    /// are lowered to fit
+    #[serde(default)]
+    pub target_size: Option<u64>,
+    
+    /// Smallest similarity to the resized image; quality is lowered as far
+    /// as this allows
+    #[serde(default)]
+    pub min_similarity: Option<SimilarityTarget>,
+    
+    /// Unsharp mask applied after resizing
+    #[serde(default)]
+    pub sharpen: Option<SharpenOptions>,
+    
     /// File naming configuration
     pub naming: NamingConfig,
 }
 
+impl Default for ProcessingProfile {
+    /// No resize (scale 1.0) at quality 90, keeping the input format, with the
+    /// default `_resized` filename suffix
+    fn default() -> Self {
+        Self {
+            resize_mode: ResizeMode::Scale { factor: 1.0 },
+            quality: 90,
+            format: None,
+            filter: FilterType::default(),
+            webp: WebPOptions::default(),
+            avif: AvifOptions::default(),
+            poster: false,
+            auto_orient: true,
+            metadata: MetadataPolicy::default(),
+            color_space: ColorSpace::default(),
+            linear_light: false,
+            upscale: UpscalePolicy::default(),
+            target_size: None,
+            min_similarity: None,
+            sharpen: None,
+            naming: NamingConfig::default(),
+        }
+    }
+}
+
 impl ProcessingProfile {
     /// Create a new profile with scale factor
     pub fn scale(factor: f32) -> Self {
         Self {
             resize_mode: ResizeMode::Scale { factor },
-            quality: 90,
-            format: None,
-            naming: NamingConfig::default(),
+            ..Self::default()
         }
     }
     
@@ -35,9 +109,7 @@ impl ProcessingProfile {
     pub fn width(width: u32) -> Self {
         Self {
             resize_mode: ResizeMode::Width { width },
-            quality: 90,
-            format: None,
-            naming: NamingConfig::default(),
+            ..Self::default()
         }
     }
     
@@ -45,9 +117,39 @@ impl ProcessingProfile {
     pub fn height(height: u32) -> Self {
         Self {
             resize_mode: ResizeMode::Height { height },
-            quality: 90,
-            format: None,
-            naming: NamingConfig::default(),
+            ..Self::default()
+        }
+    }
+    
+    /// Create a new profile that resizes the longer side
+    pub fn long_edge(px: u32) -> Self {
+        Self {
+            resize_mode: ResizeMode::LongEdge { px },
+            ..Self::default()
+        }
+    }
+    
+    /// Create a new profile that resizes the shorter side
+    pub fn short_edge(px: u32) -> Self {
+        Self {
+            resize_mode: ResizeMode::ShortEdge { px },
+            ..Self::default()
+        }
+    }
+    
+    /// Create a new profile that shrinks images to a pixel budget
+    pub fn max_pixels(megapixels: f32) -> Self {
+        Self {
+            resize_mode: ResizeMode::MaxPixels { megapixels },
+            ..Self::default()
+        }
+    }
+    
+    /// Create a new profile that fits within a print size at a resolution
+    pub fn physical(width: f32, height: f32, unit: LengthUnit, dpi: f32) -> Self {
+        Self {
+            resize_mode: ResizeMode::Physical { width, height, unit, dpi },
+            ..Self::default()
         }
     }
     
@@ -55,19 +157,15 @@ impl ProcessingProfile {
     pub fn fit(width: u32, height: u32) -> Self {
         Self {
             resize_mode: ResizeMode::Fit { width, height },
-            quality: 90,
-            format: None,
-            naming: NamingConfig::default(),
+            ..Self::default()
         }
     }
     
     /// Create a new profile that fills dimensions
     pub fn fill(width: u32, height: u32) -> Self {
         Self {
-            resize_mode: ResizeMode::Fill { width, height },
-            quality: 90,
-            format: None,
-            naming: NamingConfig::default(),
+            resize_mode: ResizeMode::fill(width, height),
+            ..Self::default()
         }
     }
     
@@ -83,6 +181,86 @@ impl ProcessingProfile {
         self
     }
     
+    /// Set the resampling filter
+    pub fn filter(mut self, filter: FilterType) -> Self {
+        self.filter = filter;
+        self
+    }
+    
+    /// Set the crop strategy (fill mode only)
+    pub fn crop_strategy(mut self, strategy: CropStrategy) -> Self {
+        if let ResizeMode::Fill { crop, .. } = &mut self.resize_mode {
+            *crop = strategy;
+        }
+        self
+    }
+    
+    /// Set the WebP encoder options
+    pub fn webp(mut self, webp: WebPOptions) -> Self {
+        self.webp = webp;
+        self
+    }
+    
+    /// Set the AVIF encoder options
+    pub fn avif(mut self, avif: AvifOptions) -> Self {
+        self.avif = avif;
+        self
+    }
+    
+    /// Keep only the first frame of animated inputs
+    pub fn poster(mut self, poster: bool) -> Self {
+        self.poster = poster;
+        self
+    }
+    
+    /// Enable or disable EXIF auto-orientation
+    pub fn auto_orient(mut self, auto_orient: bool) -> Self {
+        self.auto_orient = auto_orient;
+        self
+    }
+    
+    /// Set the metadata policy
+    pub fn metadata(mut self, metadata: MetadataPolicy) -> Self {
+        self.metadata = metadata;
+        self
+    }
+    
+    /// Set the output color space
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+    
+    /// Resample in linear light
+    pub fn linear_light(mut self, linear_light: bool) -> Self {
+        self.linear_light = linear_light;
+        self
+    }
+    
+    /// Set the upscale policy
+    pub fn upscale(mut self, upscale: UpscalePolicy) -> Self {
+        self.upscale = upscale;
+        self
+    }
+    
+    /// Set the largest output size in bytes
+    pub fn target_size(mut self, bytes: u64) -> Self {
+        self.target_size = Some(bytes);
+        self
+    }
+    
+    /// Set the smallest similarity to the resized image
+    pub fn min_similarity(mut self, target: SimilarityTarget) -> Self {
+        self.min_similarity = Some(target);
+        self
+    }
+    
+    /// Sharpen after resizing
+    pub fn sharpen(mut self, sharpen: SharpenOptions) -> Self {
+        self.sharpen = Some(sharpen);
+        self
+    }
+    
     /// Set the naming configuration
     pub fn naming(mut self, naming: NamingConfig) -> Self {
         self.naming = naming;
@@ -107,26 +285,387 @@ impl ProcessingProfile {
                     ));
                 }
             }
-            ResizeMode::Width { width } | ResizeMode::Height { height: width } => {
+            ResizeMode::Width { width }
+            | ResizeMode::Height { height: width }
+            | ResizeMode::LongEdge { px: width }
+            | ResizeMode::ShortEdge { px: width } => {
                 if *width == 0 || *width > 32768 {
                     return Err(FastResizeError::invalid_parameters(
                         format!("Dimension must be between 1-32768, got {}", width)
                     ));
                 }
             }
-            ResizeMode::Fit { width, height } | ResizeMode::Fill { width, height } => {
+            ResizeMode::Fit { width, height }
+            | ResizeMode::Fill { width, height, .. }
+            | ResizeMode::Pad { width, height, .. } => {
                 if *width == 0 || *width > 32768 || *height == 0 || *height > 32768 {
                     return Err(FastResizeError::invalid_parameters(
                         format!("Dimensions must be between 1-32768, got {}x{}", width, height)
                     ));
                 }
             }
+            ResizeMode::MaxPixels { megapixels } => {
+                if !(*megapixels > 0.0 && *megapixels <= 1000.0) {
+                    return Err(FastResizeError::invalid_parameters(
+                        format!("Pixel budget must be between 0.0-1000.0 megapixels, got {}", megapixels)
+                    ));
+                }
+            }
+            ResizeMode::Physical { width, height, unit, dpi } => {
+                if !(*dpi > 0.0 && *dpi <= 10000.0) {
+                    return Err(FastResizeError::invalid_parameters(
+                        format!("Resolution must be between 0-10000 DPI, got {}", dpi)
+                    ));
+                }
+                let pixels = |length: f32| length.is_finite().then(|| unit.to_pixels(length, *dpi));
+                if !matches!((pixels(*width), pixels(*height)), (Some(1..=32768), Some(1..=32768))) {
+                    return Err(FastResizeError::invalid_parameters(
+                        format!("Print size must be 1-32768 pixels at {} DPI, got {}x{} {:?}", dpi, width, height, unit)
+                    ));
+                }
+            }
+        }
+        
+        // Validate crop focus point
+        if let ResizeMode::Fill { crop: CropStrategy::Focus { x, y }, .. } = &self.resize_mode {
+            if !(0.0..=1.0).contains(x) || !(0.0..=1.0).contains(y) {
+                return Err(FastResizeError::invalid_parameters(
+                    format!("Focus point must be between 0.0-1.0, got ({}, {})", x, y)
+                ));
+            }
+        }
+        
+        self.webp.validate()?;
+        self.avif.validate()?;
+        self.upscale.validate()?;
+        
+        if self.target_size == Some(0) {
+            return Err(FastResizeError::invalid_parameters(
+                "Target size must be greater than 0 bytes"
+            ));
+        }
+        
+        if let Some(target) = &self.min_similarity {
+            target.validate()?;
+        }
+        if let Some(sharpen) = &self.sharpen {
+            sharpen.validate()?;
         }
         
         self.naming.validate()
     }
 }
 
+fn default_true() -> bool {
+    true
+}
+
+/// WebP encoder options
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WebPOptions {
+    /// Encode losslessly instead of at the profile quality
+    pub lossless: bool,
+    
+    /// Compression effort (0 = fastest, 6 = smallest)
+    pub method: u8,
+    
+    /// Near-lossless preprocessing level for lossless output (0-100, 100 = off)
+    pub near_lossless: Option<u8>,
+}
+
+impl Default for WebPOptions {
+    fn default() -> Self {
+        Self {
+            lossless: false,
+            method: 4,
+            near_lossless: None,
+        }
+    }
+}
+
+impl WebPOptions {
+    /// Lossless encoding with default effort
+    pub fn lossless() -> Self {
+        Self {
+            lossless: true,
+            ..Default::default()
+        }
+    }
+    
+    /// Validate WebP options
+    pub fn validate(&self) -> Result<()> {
+        if self.method > 6 {
+            return Err(FastResizeError::invalid_parameters(
+                format!("WebP method must be between 0-6, got {}", self.method)
+            ));
+        }
+        
+        if let Some(level) = self.near_lossless {
+            if level > 100 {
+                return Err(FastResizeError::invalid_parameters(
+                    format!("WebP near-lossless level must be between 0-100, got {}", level)
+                ));
+            }
+            if !self.lossless {
+                return Err(FastResizeError::invalid_parameters(
+                    "WebP near-lossless requires lossless encoding"
+                ));
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// AVIF encoder options
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AvifOptions {
+    /// Encoder speed (0 = slowest and smallest, 10 = fastest)
+    pub speed: u8,
+    
+    /// Chroma subsampling of the color planes
+    pub subsampling: ChromaSubsampling,
+}
+
+impl Default for AvifOptions {
+    fn default() -> Self {
+        Self {
+            speed: 6,
+            subsampling: ChromaSubsampling::default(),
+        }
+    }
+}
+
+impl AvifOptions {
+    /// Validate AVIF options
+    pub fn validate(&self) -> Result<()> {
+        if self.speed > 10 {
+            return Err(FastResizeError::invalid_parameters(
+                format!("AVIF speed must be between 0-10, got {}", self.speed)
+            ));
+        }
+        
+        Ok(())
+    }
+}
+
+/// Chroma subsampling for YCbCr encoders
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ChromaSubsampling {
+    /// Full-resolution chroma, best for text and sharp color edges
+    #[serde(rename = "444")]
+    Yuv444,
+    /// Half-resolution chroma in both directions, smallest files
+    #[default]
+    #[serde(rename = "420")]
+    Yuv420,
+}
+
+/// Which metadata survives into the output
+///
+/// Applies to EXIF, XMP and IPTC blocks carried between JPEG, PNG, WebP and
+/// TIFF files; other output formats are always written without metadata.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataPolicy {
+    /// Write no metadata
+    #[default]
+    StripAll,
+    /// Carry all metadata unchanged
+    KeepAll,
+    /// Carry only author and copyright fields
+    CopyrightOnly,
+    /// Carry everything except GPS location
+    StripGps,
+}
+
+/// How far an image may be enlarged to reach its target size
+///
+/// Applies to every resize mode. Pad mode keeps its canvas size and only
+/// limits the image placed on it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpscalePolicy {
+    /// Enlarge as far as the target needs
+    #[default]
+    Always,
+    /// Never enlarge; small images keep their size
+    Never,
+    /// Enlarge by at most this factor
+    UpToFactor(f32),
+}
+
+impl UpscalePolicy {
+    /// Largest allowed scale factor, or `None` when unlimited
+    pub fn max_factor(self) -> Option<f32> {
+        match self {
+            Self::Always => None,
+            Self::Never => Some(1.0),
+            Self::UpToFactor(factor) => Some(factor),
+        }
+    }
+    
+    /// Validate the upscale policy
+    pub fn validate(&self) -> Result<()> {
+        if let Self::UpToFactor(factor) = self {
+            if !factor.is_finite() || *factor < 1.0 {
+                return Err(FastResizeError::invalid_parameters(
+                    format!("Upscale factor must be at least 1.0, got {}", factor)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Smallest structural similarity the encoded output may have to the
+/// resized image; the lowest quality that reaches it is used
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityTarget {
+    /// Minimum SSIM score
+    Ssim(f32),
+    /// Minimum multi-scale SSIM score
+    MsSsim(f32),
+}
+
+impl SimilarityTarget {
+    /// Score the output must reach
+    pub fn min_score(self) -> f64 {
+        match self {
+            Self::Ssim(score) | Self::MsSsim(score) => f64::from(score),
+        }
+    }
+    
+    /// Validate the similarity target
+    pub fn validate(&self) -> Result<()> {
+        let score = self.min_score();
+        if !(score > 0.0 && score <= 1.0) {
+            return Err(FastResizeError::invalid_parameters(
+                format!("Similarity score must be between 0.0-1.0, got {}", score)
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Unsharp mask applied after resizing
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SharpenOptions {
+    /// Strength, or `auto` to follow the downscale factor
+    #[serde(default)]
+    pub amount: SharpenAmount,
+    
+    /// Gaussian blur radius (sigma) in pixels
+    #[serde(default = "default_sharpen_radius")]
+    pub radius: f32,
+    
+    /// Smallest per-channel difference, in 0-255 levels, that gets sharpened
+    #[serde(default)]
+    pub threshold: u8,
+}
+
+impl Default for SharpenOptions {
+    fn default() -> Self {
+        Self {
+            amount: SharpenAmount::Auto,
+            radius: default_sharpen_radius(),
+            threshold: 0,
+        }
+    }
+}
+
+impl SharpenOptions {
+    /// Validate the sharpening options
+    pub fn validate(&self) -> Result<()> {
+        if !(self.radius > 0.0 && self.radius <= 10.0) {
+            return Err(FastResizeError::invalid_parameters(
+                format!("Sharpen radius must be between 0.0-10.0 pixels, got {}", self.radius)
+            ));
+        }
+        if let SharpenAmount::Fixed(amount) = self.amount {
+            if !(0.0..=5.0).contains(&amount) {
+                return Err(FastResizeError::invalid_parameters(
+                    format!("Sharpen amount must be between 0.0-5.0, got {}", amount)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_sharpen_radius() -> f32 {
+    0.8
+}
+
+/// Unsharp mask strength, written as a number or `auto`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(try_from = "AmountValue", into = "AmountValue")]
+pub enum SharpenAmount {
+    /// Grow with the downscale factor: none at 1x, 0.5 at 4x, at most 1.0
+    #[default]
+    Auto,
+    /// Fixed amount; 1.0 adds the full difference from the blurred image
+    Fixed(f32),
+}
+
+impl SharpenAmount {
+    /// Amount for an image shrunk by `downscale` (original over output size)
+    pub fn resolve(self, downscale: f32) -> f32 {
+        match self {
+            Self::Auto if downscale > 1.0 => (0.25 * downscale.log2()).min(1.0),
+            Self::Auto => 0.0,
+            Self::Fixed(amount) => amount,
+        }
+    }
+}
+
+impl FromStr for SharpenAmount {
+    type Err = FastResizeError;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        if s == "auto" {
+            return Ok(Self::Auto);
+        }
+        s.parse::<f32>()
+            .map(Self::Fixed)
+            .map_err(|_| FastResizeError::invalid_parameters(
+                format!("Invalid sharpen amount '{}', expected auto or a number", s)
+            ))
+    }
+}
+
+/// Serialized form of `SharpenAmount`
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum AmountValue {
+    Fixed(f32),
+    Name(String),
+}
+
+impl TryFrom<AmountValue> for SharpenAmount {
+    type Error = FastResizeError;
+    
+    fn try_from(value: AmountValue) -> Result<Self> {
+        match value {
+            AmountValue::Fixed(amount) => Ok(Self::Fixed(amount)),
+            AmountValue::Name(name) => name.parse(),
+        }
+    }
+}
+
+impl From<SharpenAmount> for AmountValue {
+    fn from(amount: SharpenAmount) -> Self {
+        match amount {
+            SharpenAmount::Auto => Self::Name("auto".to_string()),
+            SharpenAmount::Fixed(amount) => Self::Fixed(amount),
+        }
+    }
+}
+
 /// File naming configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NamingConfig {
@@ -249,10 +788,14 @@ impl Profiles {
             resize_mode: ResizeMode::Width { width: 1920 },
             quality: 85,
             format: Some(ImageFormat::WebP),
+            metadata: MetadataPolicy::CopyrightOnly,
+            color_space: ColorSpace::Srgb,
+            upscale: UpscalePolicy::Never,
             naming: NamingConfig {
                 suffix: Some("_web".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
     
@@ -262,10 +805,14 @@ impl Profiles {
             resize_mode: ResizeMode::Width { width: 768 },
             quality: 75,
             format: Some(ImageFormat::WebP),
+            metadata: MetadataPolicy::CopyrightOnly,
+            color_space: ColorSpace::Srgb,
+            upscale: UpscalePolicy::Never,
             naming: NamingConfig {
                 suffix: Some("_mobile".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
     
@@ -275,36 +822,47 @@ impl Profiles {
             resize_mode: ResizeMode::Fit { width: 300, height: 300 },
             quality: 80,
             format: Some(ImageFormat::WebP),
+            metadata: MetadataPolicy::CopyrightOnly,
+            color_space: ColorSpace::Srgb,
+            upscale: UpscalePolicy::Never,
+            sharpen: Some(SharpenOptions::default()),
             naming: NamingConfig {
                 suffix: Some("_thumb".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
     
-    /// High-quality print profile
+    /// High-quality print profile: 10 inches on the long edge at 300 DPI
     pub fn print() -> ProcessingProfile {
         ProcessingProfile {
-            resize_mode: ResizeMode::Width { width: 3000 },
+            resize_mode: ResizeMode::Physical { width: 10.0, height: 10.0, unit: LengthUnit::Inch, dpi: 300.0 },
             quality: 95,
             format: None, // Keep original format
+            metadata: MetadataPolicy::KeepAll,
+            color_space: ColorSpace::Auto,
             naming: NamingConfig {
                 suffix: Some("_print".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
     
     /// Social media profile (square crop)
     pub fn social() -> ProcessingProfile {
         ProcessingProfile {
-            resize_mode: ResizeMode::Fill { width: 1080, height: 1080 },
+            resize_mode: ResizeMode::fill(1080, 1080),
             quality: 85,
             format: Some(ImageFormat::Jpeg),
+            metadata: MetadataPolicy::CopyrightOnly,
+            color_space: ColorSpace::Srgb,
             naming: NamingConfig {
                 suffix: Some("_social".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
     
@@ -314,10 +872,15 @@ impl Profiles {
             resize_mode: ResizeMode::Width { width: 800 },
             quality: 70,
             format: Some(ImageFormat::Jpeg),
+            metadata: MetadataPolicy::CopyrightOnly,
+            color_space: ColorSpace::Srgb,
+            upscale: UpscalePolicy::Never,
+            target_size: Some(500 * 1024),
             naming: NamingConfig {
                 suffix: Some("_email".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
     
@@ -327,10 +890,13 @@ impl Profiles {
             resize_mode: ResizeMode::Scale { factor: 1.0 }, // No resize
             quality: 100,
             format: Some(ImageFormat::Png),
+            metadata: MetadataPolicy::KeepAll,
+            color_space: ColorSpace::Auto,
             naming: NamingConfig {
                 suffix: Some("_archive".to_string()),
                 ..Default::default()
             },
+            ..Default::default()
         }
     }
     
@@ -354,6 +920,18 @@ pub struct ResizeConfig {
     pub mode: ResizeMode,
     pub quality: u8,
     pub format: Option<ImageFormat>,
+    pub filter: FilterType,
+    pub webp: WebPOptions,
+    pub avif: AvifOptions,
+    pub poster: bool,
+    pub auto_orient: bool,
+    pub metadata: MetadataPolicy,
+    pub color_space: ColorSpace,
+    pub linear_light: bool,
+    pub upscale: UpscalePolicy,
+    pub target_size: Option<u64>,
+    pub min_similarity: Option<SimilarityTarget>,
+    pub sharpen: Option<SharpenOptions>,
 }
 
 impl ResizeConfig {
@@ -363,6 +941,18 @@ impl ResizeConfig {
             mode: ResizeMode::Scale { factor: 1.0 },
             quality: 90,
             format: None,
+            filter: FilterType::default(),
+            webp: WebPOptions::default(),
+            avif: AvifOptions::default(),
+            poster: false,
+            auto_orient: true,
+            metadata: MetadataPolicy::default(),
+            color_space: ColorSpace::default(),
+            linear_light: false,
+            upscale: UpscalePolicy::default(),
+            target_size: None,
+            min_similarity: None,
+            sharpen: None,
         }
     }
     
@@ -390,6 +980,30 @@ impl ResizeConfig {
         self
     }
     
+    /// Resize the longer side
+    pub fn long_edge(mut self, px: u32) -> Self {
+        self.mode = ResizeMode::LongEdge { px };
+        self
+    }
+    
+    /// Resize the shorter side
+    pub fn short_edge(mut self, px: u32) -> Self {
+        self.mode = ResizeMode::ShortEdge { px };
+        self
+    }
+    
+    /// Shrink to a pixel budget in megapixels
+    pub fn max_pixels(mut self, megapixels: f32) -> Self {
+        self.mode = ResizeMode::MaxPixels { megapixels };
+        self
+    }
+    
+    /// Fit within a print size, tagging the output with the resolution
+    pub fn physical(mut self, width: f32, height: f32, unit: LengthUnit, dpi: f32) -> Self {
+        self.mode = ResizeMode::Physical { width, height, unit, dpi };
+        self
+    }
+    
     /// Fit within dimensions
     pub fn fit(mut self, width: u32, height: u32) -> Self {
         self.mode = ResizeMode::Fit { width, height };
@@ -398,7 +1012,13 @@ impl ResizeConfig {
     
     /// Fill dimensions
     pub fn fill(mut self, width: u32, height: u32) -> Self {
-        self.mode = ResizeMode::Fill { width, height };
+        self.mode = ResizeMode::fill(width, height);
+        self
+    }
+    
+    /// Fill dimensions, keeping the anchored part of the image
+    pub fn fill_anchored(mut self, width: u32, height: u32, anchor: CropAnchor) -> Self {
+        self.mode = ResizeMode::Fill { width, height, anchor, crop: CropStrategy::default() };
         self
     }
     
@@ -413,6 +1033,78 @@ impl ResizeConfig {
         self.format = Some(format);
         self
     }
+    
+    /// Set resampling filter
+    pub fn filter(mut self, filter: FilterType) -> Self {
+        self.filter = filter;
+        self
+    }
+    
+    /// Set WebP encoder options
+    pub fn webp(mut self, webp: WebPOptions) -> Self {
+        self.webp = webp;
+        self
+    }
+    
+    /// Set AVIF encoder options
+    pub fn avif(mut self, avif: AvifOptions) -> Self {
+        self.avif = avif;
+        self
+    }
+    
+    /// Keep only the first frame of animated inputs
+    pub fn poster(mut self, poster: bool) -> Self {
+        self.poster = poster;
+        self
+    }
+    
+    /// Enable or disable EXIF auto-orientation
+    pub fn auto_orient(mut self, auto_orient: bool) -> Self {
+        self.auto_orient = auto_orient;
+        self
+    }
+    
+    /// Set the metadata policy
+    pub fn metadata(mut self, metadata: MetadataPolicy) -> Self {
+        self.metadata = metadata;
+        self
+    }
+    
+    /// Set the output color space
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+    
+    /// Resample in linear light
+    pub fn linear_light(mut self, linear_light: bool) -> Self {
+        self.linear_light = linear_light;
+        self
+    }
+    
+    /// Set the upscale policy
+    pub fn upscale(mut self, upscale: UpscalePolicy) -> Self {
+        self.upscale = upscale;
+        self
+    }
+    
+    /// Set the largest output size in bytes
+    pub fn target_size(mut self, bytes: u64) -> Self {
+        self.target_size = Some(bytes);
+        self
+    }
+    
+    /// Set the smallest similarity to the resized image
+    pub fn min_similarity(mut self, target: SimilarityTarget) -> Self {
+        self.min_similarity = Some(target);
+        self
+    }
+    
+    /// Sharpen after resizing
+    pub fn sharpen(mut self, sharpen: SharpenOptions) -> Self {
+        self.sharpen = Some(sharpen);
+        self
+    }
 }
 
 impl Default for ResizeConfig {
@@ -421,6 +1113,28 @@ impl Default for ResizeConfig {
     }
 }
 
+impl From<&ProcessingProfile> for ResizeConfig {
+    fn from(profile: &ProcessingProfile) -> Self {
+        Self {
+            mode: profile.resize_mode.clone(),
+            quality: profile.quality,
+            format: profile.format,
+            filter: profile.filter,
+            webp: profile.webp.clone(),
+            avif: profile.avif,
+            poster: profile.poster,
+            auto_orient: profile.auto_orient,
+            metadata: profile.metadata,
+            color_space: profile.color_space,
+            linear_light: profile.linear_light,
+            upscale: profile.upscale,
+            target_size: profile.target_size,
+            min_similarity: profile.min_similarity,
+            sharpen: profile.sharpen,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -440,12 +1154,12 @@ mod tests {
     fn test_profile_validation() {
         let valid_profile = ProcessingProfile::width(1920);
         assert!(valid_profile.validate().is_ok());
+        assert!(ProcessingProfile::default().validate().is_ok());
         
         let invalid_profile = ProcessingProfile {
             resize_mode: ResizeMode::Scale { factor: 0.0 },
             quality: 101,
-            format: None,
-            naming: NamingConfig::default(),
+            ..Default::default()
         };
         assert!(invalid_profile.validate().is_err());
     }
@@ -488,6 +1202,160 @@ mod tests {
         assert_eq!(config.format, Some(ImageFormat::WebP));
     }
 
+    #[test]
+    fn test_resize_config_from_profile() {
+        let profile = Profiles::social().filter(FilterType::Auto);
+        let config = ResizeConfig::from(&profile);
+
+        assert!(matches!(config.mode, ResizeMode::Fill { width: 1080, height: 1080, .. }));
+        assert_eq!(config.quality, 85);
+        assert_eq!(config.format, Some(ImageFormat::Jpeg));
+        assert_eq!(config.filter, FilterType::Auto);
+    }
+
+    #[test]
+    fn test_profile_filter_serialization() {
+        let profile = ProcessingProfile::width(64).filter(FilterType::Nearest);
+        let mut value = serde_json::to_value(&profile).unwrap();
+        assert_eq!(value["filter"], "nearest");
+
+        let restored: ProcessingProfile = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(restored.filter, FilterType::Nearest);
+
+        // Profiles written before the filter option keep the default filter
+        value.as_object_mut().unwrap().remove("filter");
+        let restored: ProcessingProfile = serde_json::from_value(value).unwrap();
+        assert_eq!(restored.filter, FilterType::Lanczos3);
+    }
+
+    #[test]
+    fn test_edge_and_budget_validation() {
+        assert!(ProcessingProfile::long_edge(2048).validate().is_ok());
+        assert!(ProcessingProfile::short_edge(0).validate().is_err());
+        assert!(ProcessingProfile::long_edge(40000).validate().is_err());
+        assert!(ProcessingProfile::max_pixels(12.0).validate().is_ok());
+        assert!(ProcessingProfile::max_pixels(0.0).validate().is_err());
+        assert!(ProcessingProfile::max_pixels(f32::NAN).validate().is_err());
+
+        let profile: ProcessingProfile = serde_json::from_value(serde_json::json!({
+            "resize_mode": { "type": "max_pixels", "megapixels": 2.5 },
+            "quality": 85,
+            "format": null,
+            "naming": NamingConfig::default(),
+        })).unwrap();
+        assert!(matches!(profile.resize_mode, ResizeMode::MaxPixels { megapixels } if megapixels == 2.5));
+    }
+
+    #[test]
+    fn test_physical_validation() {
+        assert!(Profiles::print().validate().is_ok());
+        assert!(ProcessingProfile::physical(150.0, 100.0, LengthUnit::Millimeter, 300.0).validate().is_ok());
+        assert!(ProcessingProfile::physical(6.0, 4.0, LengthUnit::Inch, 0.0).validate().is_err());
+        assert!(ProcessingProfile::physical(-6.0, 4.0, LengthUnit::Inch, 300.0).validate().is_err());
+        assert!(ProcessingProfile::physical(6.0, f32::NAN, LengthUnit::Inch, 300.0).validate().is_err());
+        // 200 inches at 300 DPI is 60000 pixels
+        assert!(ProcessingProfile::physical(200.0, 4.0, LengthUnit::Inch, 300.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_upscale_policy_profile() {
+        assert_eq!(Profiles::web().upscale, UpscalePolicy::Never);
+        assert_eq!(ProcessingProfile::width(64).upscale, UpscalePolicy::Always);
+
+        let profile = ProcessingProfile::width(64).upscale(UpscalePolicy::UpToFactor(2.0));
+        let value = serde_json::to_value(&profile).unwrap();
+        assert_eq!(value["upscale"], serde_json::json!({ "up_to_factor": 2.0 }));
+        assert_eq!(ResizeConfig::from(&profile).upscale, UpscalePolicy::UpToFactor(2.0));
+
+        assert!(ProcessingProfile::width(64).upscale(UpscalePolicy::UpToFactor(0.5)).validate().is_err());
+    }
+
+    #[test]
+    fn test_sharpen_options() {
+        assert_eq!(Profiles::thumbnail().sharpen, Some(SharpenOptions::default()));
+        assert_eq!(Profiles::web().sharpen, None);
+
+        let sharpen: SharpenOptions = toml::from_str("amount = \"auto\"\nthreshold = 3").unwrap();
+        assert_eq!(sharpen, SharpenOptions { threshold: 3, ..SharpenOptions::default() });
+        let sharpen: SharpenOptions = toml::from_str("amount = 0.5\nradius = 1.2").unwrap();
+        assert_eq!(sharpen.amount, SharpenAmount::Fixed(0.5));
+        assert_eq!(serde_json::to_value(sharpen).unwrap()["amount"], 0.5);
+        assert_eq!(serde_json::to_value(SharpenOptions::default()).unwrap()["amount"], "auto");
+        assert!(toml::from_str::<SharpenOptions>("amount = \"strong\"").is_err());
+
+        let profile = ProcessingProfile::width(64).sharpen(sharpen);
+        assert!(profile.validate().is_ok());
+        assert_eq!(ResizeConfig::from(&profile).sharpen, Some(sharpen));
+        let sharpen = SharpenOptions { radius: 0.0, ..sharpen };
+        assert!(ProcessingProfile::width(64).sharpen(sharpen).validate().is_err());
+        let sharpen = SharpenOptions { amount: SharpenAmount::Fixed(-1.0), ..SharpenOptions::default() };
+        assert!(ProcessingProfile::width(64).sharpen(sharpen).validate().is_err());
+    }
+
+    #[test]
+    fn test_similarity_target_profile() {
+        let profile = ProcessingProfile::width(64).min_similarity(SimilarityTarget::Ssim(0.98));
+        assert!(profile.validate().is_ok());
+        assert_eq!(ResizeConfig::from(&profile).min_similarity, Some(SimilarityTarget::Ssim(0.98)));
+
+        let value = serde_json::to_value(&profile).unwrap();
+        assert_eq!(value["min_similarity"], serde_json::json!({ "ssim": 0.98f32 }));
+
+        assert!(ProcessingProfile::width(64).min_similarity(SimilarityTarget::MsSsim(0.0)).validate().is_err());
+        assert!(ProcessingProfile::width(64).min_similarity(SimilarityTarget::MsSsim(1.5)).validate().is_err());
+    }
+
+    #[test]
+    fn test_crop_strategy_profile() {
+        let profile = ProcessingProfile::fill(400, 400).crop_strategy(CropStrategy::Attention);
+        assert!(matches!(profile.resize_mode, ResizeMode::Fill { crop: CropStrategy::Attention, .. }));
+        assert!(profile.validate().is_ok());
+
+        let invalid = ProcessingProfile::fill(400, 400).crop_strategy(CropStrategy::Focus { x: 1.5, y: 0.5 });
+        assert!(invalid.validate().is_err());
+
+        // Non-fill modes ignore the crop strategy
+        let fit = ProcessingProfile::fit(400, 400).crop_strategy(CropStrategy::Attention);
+        assert!(matches!(fit.resize_mode, ResizeMode::Fit { .. }));
+
+        let json = r#"{"type":"fill","width":300,"height":200,"crop":{"strategy":"focus","x":0.25,"y":0.4}}"#;
+        let mode: ResizeMode = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            mode,
+            ResizeMode::Fill { anchor: CropAnchor::Center, crop: CropStrategy::Focus { .. }, .. }
+        ));
+    }
+
+    #[test]
+    fn test_webp_options_validation() {
+        assert!(WebPOptions::default().validate().is_ok());
+        assert!(WebPOptions::lossless().validate().is_ok());
+
+        let too_slow = WebPOptions { method: 7, ..Default::default() };
+        assert!(too_slow.validate().is_err());
+
+        let near_lossless = WebPOptions { near_lossless: Some(60), ..WebPOptions::lossless() };
+        assert!(near_lossless.validate().is_ok());
+
+        let lossy_near_lossless = WebPOptions { near_lossless: Some(60), ..Default::default() };
+        assert!(lossy_near_lossless.validate().is_err());
+
+        let profile = ProcessingProfile::width(800).webp(WebPOptions { method: 9, ..Default::default() });
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_avif_options() {
+        assert!(AvifOptions::default().validate().is_ok());
+        assert_eq!(AvifOptions::default().subsampling, ChromaSubsampling::Yuv420);
+
+        let profile = ProcessingProfile::width(800).avif(AvifOptions { speed: 11, ..Default::default() });
+        assert!(profile.validate().is_err());
+
+        let options: AvifOptions = serde_json::from_str(r#"{"speed": 3, "subsampling": "444"}"#).unwrap();
+        assert_eq!(options, AvifOptions { speed: 3, subsampling: ChromaSubsampling::Yuv444 });
+    }
+
     #[test]
     fn test_filename_generation() {
         let naming = NamingConfig::default();
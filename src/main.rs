@@ -13,9 +13,11 @@ use indicatif::{ProgressBar, ProgressStyle};
 use tracing::{info, warn, error, debug};
 
 use fastresize::{
-    Config, ProcessingEngine, ResizeConfig, ResizeMode, ImageFormat,
+    Config, ResizeConfig, ResizeMode, ImageFormat,
     init,
 };
+use fastresize::config::{Background, ChromaSubsampling, ColorSpace, CropAnchor, CropStrategy, FilterType, LengthUnit, MetadataPolicy, NamingConfig, ProcessingProfile, Profiles, SharpenAmount, SharpenOptions, SimilarityTarget, UpscalePolicy};
+use fastresize::parallel::{ParallelProcessor, ProcessingStrategy, ProgressUpdate};
 
 /// FastResize - High-Performance Batch Image Resizer
 #[derive(Parser)]
@@ -52,31 +54,143 @@ struct Cli {
     #[arg(short = 'H', long, value_name = "PIXELS", conflicts_with_all = ["scale", "width"])]
     height: Option<u32>,
 
+    /// Resize the longer side to this many pixels (landscape and portrait alike)
+    #[arg(long, value_name = "PIXELS", conflicts_with_all = ["scale", "width", "height", "fit", "fill", "pad", "short_edge", "max_pixels"])]
+    long_edge: Option<u32>,
+
+    /// Resize the shorter side to this many pixels
+    #[arg(long, value_name = "PIXELS", conflicts_with_all = ["scale", "width", "height", "fit", "fill", "pad", "long_edge", "max_pixels"])]
+    short_edge: Option<u32>,
+
+    /// Shrink images larger than this many megapixels, keeping aspect ratio
+    #[arg(long, value_name = "MEGAPIXELS", conflicts_with_all = ["scale", "width", "height", "fit", "fill", "pad", "long_edge", "short_edge"])]
+    max_pixels: Option<f32>,
+
     /// Fit within dimensions (width x height)
-    #[arg(long, value_name = "WxH", value_parser = parse_dimensions, conflicts_with_all = ["scale", "width", "height", "fill"])]
+    #[arg(long, value_name = "WxH", value_parser = parse_dimensions, conflicts_with_all = ["scale", "width", "height", "fill", "pad"])]
     fit: Option<(u32, u32)>,
 
     /// Fill dimensions exactly (may crop)
-    #[arg(long, value_name = "WxH", value_parser = parse_dimensions, conflicts_with_all = ["scale", "width", "height", "fit"])]
+    #[arg(long, value_name = "WxH", value_parser = parse_dimensions, conflicts_with_all = ["scale", "width", "height", "fit", "pad"])]
     fill: Option<(u32, u32)>,
 
-    /// Output quality (1-100)
-    #[arg(short, long, default_value = "90", value_name = "QUALITY")]
-    quality: u8,
+    /// Fit within dimensions, then pad to exactly that size (letterbox)
+    #[arg(long, value_name = "WxH", value_parser = parse_dimensions, conflicts_with_all = ["scale", "width", "height", "fit", "fill"])]
+    pad: Option<(u32, u32)>,
+
+    /// Fit within a print size at --dpi, e.g. 6x4in or 150x100mm; the output is tagged with the DPI
+    #[arg(long, value_name = "WxH<mm|in>", value_parser = parse_print_size, requires = "dpi", conflicts_with_all = ["scale", "width", "height", "fit", "fill", "pad", "long_edge", "short_edge", "max_pixels"])]
+    print_size: Option<(f32, f32, LengthUnit)>,
+
+    /// Print resolution for --print-size, in dots per inch
+    #[arg(long, value_name = "DPI", requires = "print_size")]
+    dpi: Option<f32>,
+
+    /// Padding color for --pad: #rrggbb, #rgb or transparent (white where alpha is unsupported)
+    #[arg(long, value_name = "COLOR")]
+    background: Option<Background>,
+
+    /// Part of the image kept when --fill crops, or where --pad places it
+    #[arg(long, value_enum, value_name = "ANCHOR")]
+    anchor: Option<CliAnchor>,
+
+    /// How --fill picks the crop: anchor, attention, sidecar, or a focus point X,Y (0.0-1.0)
+    #[arg(long, value_name = "STRATEGY", value_parser = parse_crop_strategy)]
+    crop_strategy: Option<CropStrategy>,
+
+    /// Output quality (1-100) [default: 90, or the profile's quality]
+    #[arg(short, long, value_name = "QUALITY")]
+    quality: Option<u8>,
 
     /// Output format
     #[arg(short, long, value_enum, value_name = "FORMAT")]
     format: Option<CliImageFormat>,
 
+    /// Encode WebP losslessly instead of at --quality
+    #[arg(long)]
+    webp_lossless: bool,
+
+    /// WebP compression effort, 0 (fastest) to 6 (smallest)
+    #[arg(long, value_name = "0-6", value_parser = clap::value_parser!(u8).range(0..=6))]
+    webp_method: Option<u8>,
+
+    /// WebP near-lossless level, 0-100 (implies --webp-lossless)
+    #[arg(long, value_name = "0-100", value_parser = clap::value_parser!(u8).range(0..=100))]
+    webp_near_lossless: Option<u8>,
+
+    /// AVIF encoder speed, 0 (slowest, smallest) to 10 (fastest)
+    #[arg(long, value_name = "0-10", value_parser = clap::value_parser!(u8).range(0..=10))]
+    avif_speed: Option<u8>,
+
+    /// AVIF chroma subsampling
+    #[arg(long, value_enum, value_name = "MODE")]
+    avif_subsampling: Option<CliChromaSubsampling>,
+
+    /// Keep only the first frame of animated GIF and WebP inputs
+    #[arg(long)]
+    poster: bool,
+
+    /// Keep stored pixel orientation instead of applying the EXIF Orientation tag
+    #[arg(long)]
+    no_auto_orient: bool,
+
+    /// EXIF/XMP/IPTC metadata to carry into the output [default: strip-all, or the profile's policy]
+    #[arg(long, value_enum, value_name = "POLICY")]
+    metadata: Option<CliMetadataPolicy>,
+
+    /// Output color space; auto keeps the input's ICC profile [default: auto, or the profile's color space]
+    #[arg(long, value_enum, value_name = "SPACE")]
+    color_space: Option<CliColorSpace>,
+
+    /// How far small images may be enlarged: never, always, or a maximum factor such as 2 [default: always, or the profile's policy]
+    #[arg(long, value_name = "POLICY", value_parser = parse_upscale)]
+    upscale: Option<UpscalePolicy>,
+
+    /// Largest output file size, e.g. 500KB or 2MB; lowers quality, then dimensions, to fit
+    #[arg(long, value_name = "SIZE", value_parser = parse_byte_size)]
+    max_bytes: Option<u64>,
+
+    /// Lowest quality whose output keeps at least this SSIM to the resized image, e.g. 0.98
+    #[arg(long, value_name = "SCORE", conflicts_with = "min_ms_ssim")]
+    min_ssim: Option<f32>,
+
+    /// Like --min-ssim, scored with multi-scale SSIM
+    #[arg(long, value_name = "SCORE")]
+    min_ms_ssim: Option<f32>,
+
+    /// Resample in linear light, keeping fine detail and edges from darkening
+    #[arg(long)]
+    linear_light: bool,
+
+    /// Sharpen after resizing: auto (stronger for bigger downscales) or an amount such as 0.5
+    #[arg(long, value_name = "AMOUNT")]
+    sharpen: Option<SharpenAmount>,
+
+    /// Blur radius of the sharpening mask in pixels, with --sharpen or a sharpening profile [default: 0.8]
+    #[arg(long, value_name = "PIXELS")]
+    sharpen_radius: Option<f32>,
+
+    /// Smallest difference (0-255) that gets sharpened, to keep noise down; with --sharpen or a sharpening profile [default: 0]
+    #[arg(long, value_name = "LEVELS")]
+    sharpen_threshold: Option<u8>,
+
+    /// Resampling filter [default: lanczos3, or the profile's filter]
+    #[arg(long, value_enum, value_name = "FILTER")]
+    filter: Option<CliFilter>,
+
     /// Number of threads (default: auto-detect)
     #[arg(short, long, value_name = "COUNT")]
     threads: Option<usize>,
 
+    /// Parallel processing strategy
+    #[arg(long, value_enum, default_value = "auto", value_name = "STRATEGY")]
+    strategy: CliStrategy,
+
     /// Configuration file path
     #[arg(short, long, value_name = "FILE")]
     config: Option<PathBuf>,
 
-    /// Processing profile name
+    /// Processing profile name (from --config, or a built-in profile)
     #[arg(short, long, value_name = "NAME")]
     profile: Option<String>,
 
@@ -96,7 +210,7 @@ struct Cli {
     #[arg(short = 'd', long, conflicts_with = "dry_run")]
     delete_originals: bool,
 
-    /// Output progress as JSON
+    /// Output progress as JSON Lines (one event object per line on stdout)
     #[arg(long)]
     json: bool,
 
@@ -136,12 +250,24 @@ enum Commands {
     Info,
     /// Run performance benchmarks
     Benchmark {
-        /// Test image size (small, medium, large)
+        /// Test image sizes (small, medium, large or all; comma-separated)
         #[arg(short, long, default_value = "medium")]
         size: String,
         /// Number of iterations
         #[arg(short, long, default_value = "10")]
         iterations: u32,
+        /// Formats to benchmark (default: all; comma-separated)
+        #[arg(short, long, value_enum, value_delimiter = ',', value_name = "FORMAT")]
+        format: Vec<CliImageFormat>,
+        /// Write results to a JSON file
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+        /// Compare against a saved JSON baseline
+        #[arg(short, long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+        /// Median slowdown (percent) reported as a regression
+        #[arg(long, default_value = "10", value_name = "PERCENT")]
+        threshold: f64,
     },
 }
 
@@ -154,6 +280,7 @@ enum CliImageFormat {
     Gif,
     Tiff,
     Bmp,
+    Avif,
 }
 
 impl From<CliImageFormat> for ImageFormat {
@@ -165,6 +292,160 @@ impl From<CliImageFormat> for ImageFormat {
             CliImageFormat::Gif => ImageFormat::Gif,
             CliImageFormat::Tiff => ImageFormat::Tiff,
             CliImageFormat::Bmp => ImageFormat::Bmp,
+            CliImageFormat::Avif => ImageFormat::Avif,
+        }
+    }
+}
+
+/// CLI-compatible chroma subsampling enum
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliChromaSubsampling {
+    /// Full-resolution chroma
+    #[value(name = "444")]
+    Yuv444,
+    /// Half-resolution chroma (smaller files)
+    #[value(name = "420")]
+    Yuv420,
+}
+
+impl From<CliChromaSubsampling> for ChromaSubsampling {
+    fn from(subsampling: CliChromaSubsampling) -> Self {
+        match subsampling {
+            CliChromaSubsampling::Yuv444 => ChromaSubsampling::Yuv444,
+            CliChromaSubsampling::Yuv420 => ChromaSubsampling::Yuv420,
+        }
+    }
+}
+
+/// CLI-compatible metadata policy enum
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliMetadataPolicy {
+    /// Write no metadata
+    StripAll,
+    /// Carry all metadata unchanged
+    KeepAll,
+    /// Carry only author and copyright fields
+    CopyrightOnly,
+    /// Carry everything except GPS location
+    StripGps,
+}
+
+impl From<CliMetadataPolicy> for MetadataPolicy {
+    fn from(policy: CliMetadataPolicy) -> Self {
+        match policy {
+            CliMetadataPolicy::StripAll => MetadataPolicy::StripAll,
+            CliMetadataPolicy::KeepAll => MetadataPolicy::KeepAll,
+            CliMetadataPolicy::CopyrightOnly => MetadataPolicy::CopyrightOnly,
+            CliMetadataPolicy::StripGps => MetadataPolicy::StripGps,
+        }
+    }
+}
+
+/// CLI-compatible color space enum
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliColorSpace {
+    /// Convert to sRGB
+    Srgb,
+    /// Convert to Adobe RGB (1998)
+    AdobeRgb,
+    /// Convert to Display P3
+    DisplayP3,
+    /// Keep the input's profile
+    Auto,
+}
+
+impl From<CliColorSpace> for ColorSpace {
+    fn from(color_space: CliColorSpace) -> Self {
+        match color_space {
+            CliColorSpace::Srgb => ColorSpace::Srgb,
+            CliColorSpace::AdobeRgb => ColorSpace::AdobeRgb,
+            CliColorSpace::DisplayP3 => ColorSpace::DisplayP3,
+            CliColorSpace::Auto => ColorSpace::Auto,
+        }
+    }
+}
+
+/// CLI-compatible resampling filter enum
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliFilter {
+    /// Nearest neighbor (fastest, keeps pixel art crisp)
+    Nearest,
+    /// Linear interpolation (fast)
+    Triangle,
+    /// Catmull-Rom cubic spline
+    #[value(name = "catmullrom")]
+    CatmullRom,
+    /// Gaussian (soft)
+    Gaussian,
+    /// Lanczos with radius 3 (high quality)
+    Lanczos3,
+    /// Choose based on the scale factor
+    Auto,
+}
+
+impl From<CliFilter> for FilterType {
+    fn from(filter: CliFilter) -> Self {
+        match filter {
+            CliFilter::Nearest => FilterType::Nearest,
+            CliFilter::Triangle => FilterType::Triangle,
+            CliFilter::CatmullRom => FilterType::CatmullRom,
+            CliFilter::Gaussian => FilterType::Gaussian,
+            CliFilter::Lanczos3 => FilterType::Lanczos3,
+            CliFilter::Auto => FilterType::Auto,
+        }
+    }
+}
+
+/// CLI-compatible crop anchor enum
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliAnchor {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl From<CliAnchor> for CropAnchor {
+    fn from(anchor: CliAnchor) -> Self {
+        match anchor {
+            CliAnchor::Center => CropAnchor::Center,
+            CliAnchor::Top => CropAnchor::Top,
+            CliAnchor::Bottom => CropAnchor::Bottom,
+            CliAnchor::Left => CropAnchor::Left,
+            CliAnchor::Right => CropAnchor::Right,
+            CliAnchor::TopLeft => CropAnchor::TopLeft,
+            CliAnchor::TopRight => CropAnchor::TopRight,
+            CliAnchor::BottomLeft => CropAnchor::BottomLeft,
+            CliAnchor::BottomRight => CropAnchor::BottomRight,
+        }
+    }
+}
+
+/// CLI-compatible processing strategy enum
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliStrategy {
+    /// Async tasks limited by a semaphore
+    Async,
+    /// Rayon thread pool for CPU-bound work
+    Cpu,
+    /// Async processing in small chunks
+    Hybrid,
+    /// Choose based on file count and available memory
+    Auto,
+}
+
+impl From<CliStrategy> for ProcessingStrategy {
+    fn from(strategy: CliStrategy) -> Self {
+        match strategy {
+            CliStrategy::Async => ProcessingStrategy::Async,
+            CliStrategy::Cpu => ProcessingStrategy::CpuIntensive,
+            CliStrategy::Hybrid => ProcessingStrategy::Hybrid,
+            CliStrategy::Auto => ProcessingStrategy::Auto,
         }
     }
 }
@@ -188,6 +469,84 @@ fn parse_dimensions(s: &str) -> Result<(u32, u32), String> {
     Ok((width, height))
 }
 
+/// Parse a print size: WIDTHxHEIGHT followed by mm or in (e.g. "6x4in")
+fn parse_print_size(s: &str) -> Result<(f32, f32, LengthUnit), String> {
+    let s = s.trim().to_lowercase();
+    let split = s.trim_end_matches(|c: char| c.is_ascii_alphabetic()).len();
+    let unit: LengthUnit = s[split..].parse().map_err(|e: fastresize::FastResizeError| e.to_string())?;
+
+    let (width, height) = s[..split].split_once('x')
+        .ok_or_else(|| "Print size must be in format 'WIDTHxHEIGHT' with mm or in (e.g., '6x4in')".to_string())?;
+    let length = |value: &str| match value.trim().parse::<f32>() {
+        Ok(length) if length.is_finite() && length > 0.0 => Ok(length),
+        _ => Err(format!("Invalid print length '{}'", value)),
+    };
+
+    Ok((length(width)?, length(height)?, unit))
+}
+
+/// Parse a byte size: plain bytes or a number with a KB, MB or GB suffix (powers of 1024)
+fn parse_byte_size(s: &str) -> Result<u64, String> {
+    let s = s.trim().to_uppercase();
+    let digits = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier = match s[digits.len()..].trim_end_matches("IB").trim_end_matches('B') {
+        "" => 1,
+        "K" => 1024,
+        "M" => 1024 * 1024,
+        "G" => 1024 * 1024 * 1024,
+        _ => return Err("Size must be bytes or a number with a KB, MB or GB suffix".to_string()),
+    };
+    let value = digits.trim().parse::<f64>()
+        .map_err(|_| "Invalid size value".to_string())?;
+
+    let bytes = (value * multiplier as f64).round();
+    if !bytes.is_finite() || bytes < 1.0 {
+        return Err("Size must be greater than 0".to_string());
+    }
+    Ok(bytes as u64)
+}
+
+/// Parse upscale policy: `never`, `always` or a maximum factor such as `2` or `1.5x`
+fn parse_upscale(s: &str) -> Result<UpscalePolicy, String> {
+    let s = s.trim().to_lowercase();
+    match s.as_str() {
+        "never" => Ok(UpscalePolicy::Never),
+        "always" => Ok(UpscalePolicy::Always),
+        factor => {
+            let factor = factor.strip_suffix('x').unwrap_or(factor).parse::<f32>()
+                .map_err(|_| "Upscale policy must be never, always or a maximum factor such as 2".to_string())?;
+            if !factor.is_finite() || factor < 1.0 {
+                return Err("Upscale factor must be at least 1.0".to_string());
+            }
+            Ok(UpscalePolicy::UpToFactor(factor))
+        }
+    }
+}
+
+/// Parse crop strategy: `anchor`, `attention`, `sidecar` or a focus point `X,Y`
+fn parse_crop_strategy(s: &str) -> Result<CropStrategy, String> {
+    match s.to_lowercase().as_str() {
+        "anchor" => return Ok(CropStrategy::Anchor),
+        "attention" => return Ok(CropStrategy::Attention),
+        "sidecar" => return Ok(CropStrategy::Sidecar),
+        _ => {}
+    }
+
+    let point = s.strip_prefix("focus:").unwrap_or(s);
+    let (x, y) = point.split_once(',')
+        .ok_or_else(|| "Crop strategy must be anchor, attention, sidecar or a focus point 'X,Y'".to_string())?;
+    let x = x.trim().parse::<f32>()
+        .map_err(|_| "Invalid focus x value".to_string())?;
+    let y = y.trim().parse::<f32>()
+        .map_err(|_| "Invalid focus y value".to_string())?;
+
+    if !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+        return Err("Focus point coordinates must be between 0.0 and 1.0".to_string());
+    }
+
+    Ok(CropStrategy::Focus { x, y })
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
@@ -212,6 +571,14 @@ async fn main() {
         return;
     }
 
+    // Keep stdout clean for the JSON event stream
+    if cli.json {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_writer(std::io::stderr)
+            .try_init();
+    }
+
     // Initialize FastResize
     if let Err(e) = init() {
         eprintln!("{}: Failed to initialize FastResize: {}", 
@@ -232,7 +599,7 @@ async fn main() {
 
     // Load configuration if provided
     let config = if let Some(ref config_path) = cli.config {
-        match Config::from_file(&config_path) {
+        match Config::from_file(config_path) {
             Ok(config) => {
                 info!("Loaded configuration from: {:?}", config_path);
                 Some(config)
@@ -246,9 +613,9 @@ async fn main() {
         None
     };
 
-    // Create resize configuration
-    let resize_config = match create_resize_config(&cli) {
-        Ok(config) => config,
+    // Resolve the processing profile and CLI overrides
+    let profile = match resolve_profile(&cli, &config) {
+        Ok(profile) => profile,
         Err(e) => {
             eprintln!("{}: {}", style("Error").red().bold(), e);
             process::exit(1);
@@ -257,7 +624,7 @@ async fn main() {
 
     // Check for watch mode
     if cli.watch {
-        if let Err(e) = run_watch_mode(&input_path, &output_path, &resize_config, &config).await {
+        if let Err(e) = run_watch_mode(&input_path, &output_path, &profile, &config, cli.recursive).await {
             eprintln!("{}: Watch mode failed: {}", style("Error").red().bold(), e);
             process::exit(1);
         }
@@ -265,7 +632,7 @@ async fn main() {
         // Run single batch processing
         
         // Show warning if delete option is used
-        if cli.delete_originals && !cli.quiet {
+        if cli.delete_originals && !cli.quiet && !cli.json {
             println!("{}: Original files will be deleted after successful processing", 
                      style("Warning").yellow().bold());
             println!("Make sure you have backups if needed!");
@@ -273,9 +640,10 @@ async fn main() {
         }
         
         let start_time = Instant::now();
-        match run_batch_processing(&cli, &input_path, &output_path, &resize_config, &config).await {
-            Ok(results) => {
+        match run_batch_processing(&cli, &input_path, &output_path, &profile, &config).await {
+            Ok(mut results) => {
                 let duration = start_time.elapsed();
+                results.duration_secs = duration.as_secs_f64();
                 print_summary(&results, duration, cli.json);
             }
             Err(e) => {
@@ -301,41 +669,176 @@ async fn handle_subcommand(command: Commands) -> Result<(), Box<dyn std::error::
         Commands::Info => {
             show_system_info().await;
         }
-        Commands::Benchmark { size, iterations } => {
-            run_benchmark(&size, iterations).await?;
+        Commands::Benchmark { size, iterations, format, output, baseline, threshold } => {
+            run_benchmark(&size, iterations, format, output, baseline, threshold).await?;
         }
     }
     Ok(())
 }
 
-/// Create resize configuration from CLI arguments
-fn create_resize_config(cli: &Cli) -> Result<ResizeConfig, String> {
+/// Resolve the processing profile from `--profile` and explicit CLI flags
+///
+/// Profiles come from the configuration file when one is given, otherwise from
+/// the built-in set. Explicit CLI flags override individual profile fields.
+fn resolve_profile(cli: &Cli, config: &Option<Config>) -> Result<ProcessingProfile, String> {
+    let base = match (&cli.profile, config) {
+        (Some(name), Some(config)) => Some(config.get_profile(name).map_err(|e| e.to_string())?.clone()),
+        (Some(name), None) => {
+            let mut profiles = Profiles::all();
+            let profile = profiles.remove(name).ok_or_else(|| {
+                let mut available: Vec<_> = profiles.keys().cloned().collect();
+                available.sort();
+                format!("Profile '{}' not found. Available profiles: {}", name, available.join(", "))
+            })?;
+            Some(profile)
+        }
+        (None, _) => None,
+    };
+
     let mode = if let Some(factor) = cli.scale {
         if factor <= 0.0 || factor > 10.0 {
             return Err("Scale factor must be between 0.1 and 10.0".to_string());
         }
-        ResizeMode::Scale { factor }
+        Some(ResizeMode::Scale { factor })
     } else if let Some(width) = cli.width {
-        ResizeMode::Width { width }
+        Some(ResizeMode::Width { width })
     } else if let Some(height) = cli.height {
-        ResizeMode::Height { height }
+        Some(ResizeMode::Height { height })
+    } else if let Some(px) = cli.long_edge {
+        Some(ResizeMode::LongEdge { px })
+    } else if let Some(px) = cli.short_edge {
+        Some(ResizeMode::ShortEdge { px })
+    } else if let Some(megapixels) = cli.max_pixels {
+        Some(ResizeMode::MaxPixels { megapixels })
+    } else if let (Some((width, height, unit)), Some(dpi)) = (cli.print_size, cli.dpi) {
+        Some(ResizeMode::Physical { width, height, unit, dpi })
     } else if let Some((width, height)) = cli.fit {
-        ResizeMode::Fit { width, height }
+        Some(ResizeMode::Fit { width, height })
     } else if let Some((width, height)) = cli.fill {
-        ResizeMode::Fill { width, height }
+        Some(ResizeMode::fill(width, height))
     } else {
-        return Err("Must specify resize mode: --scale, --width, --height, --fit, or --fill".to_string());
+        cli.pad.map(|(width, height)| ResizeMode::pad(width, height, Background::default()))
     };
 
-    if cli.quality == 0 || cli.quality > 100 {
-        return Err("Quality must be between 1 and 100".to_string());
+    let mut profile = match (base, mode) {
+        (Some(mut profile), mode) => {
+            if let Some(mode) = mode {
+                profile.resize_mode = mode;
+            }
+            profile
+        }
+        // Without a profile outputs keep the input filename
+        (None, Some(mode)) => ProcessingProfile {
+            resize_mode: mode,
+            naming: NamingConfig {
+                suffix: None,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        (None, None) => {
+            return Err("Must specify resize mode: --scale, --width, --height, --long-edge, --short-edge, --max-pixels, --fit, --fill, --pad, or --profile".to_string());
+        }
+    };
+
+    if let Some(crop_anchor) = cli.anchor {
+        match &mut profile.resize_mode {
+            ResizeMode::Fill { anchor, .. } | ResizeMode::Pad { anchor, .. } => *anchor = crop_anchor.into(),
+            _ => return Err("--anchor requires fill or pad mode (--fill, --pad or a matching profile)".to_string()),
+        }
     }
 
-    Ok(ResizeConfig {
-        mode,
-        quality: cli.quality,
-        format: cli.format.map(Into::into),
-    })
+    if let Some(color) = cli.background {
+        match &mut profile.resize_mode {
+            ResizeMode::Pad { background, .. } => *background = color,
+            _ => return Err("--background requires pad mode (--pad or a pad profile)".to_string()),
+        }
+    }
+
+    if let Some(strategy) = cli.crop_strategy {
+        match &mut profile.resize_mode {
+            ResizeMode::Fill { crop, .. } => *crop = strategy,
+            _ => return Err("--crop-strategy requires fill mode (--fill or a fill profile)".to_string()),
+        }
+    }
+
+    if let Some(quality) = cli.quality {
+        if quality == 0 || quality > 100 {
+            return Err("Quality must be between 1 and 100".to_string());
+        }
+        profile.quality = quality;
+    }
+
+    if let Some(format) = cli.format {
+        profile.format = Some(format.into());
+    }
+
+    if let Some(filter) = cli.filter {
+        profile.filter = filter.into();
+    }
+    if cli.linear_light {
+        profile.linear_light = true;
+    }
+    if let Some(upscale) = cli.upscale {
+        profile.upscale = upscale;
+    }
+    if let Some(max_bytes) = cli.max_bytes {
+        profile.target_size = Some(max_bytes);
+    }
+    if let Some(amount) = cli.sharpen {
+        profile.sharpen = Some(SharpenOptions { amount, ..profile.sharpen.unwrap_or_default() });
+    }
+    if cli.sharpen_radius.is_some() || cli.sharpen_threshold.is_some() {
+        let Some(sharpen) = &mut profile.sharpen else {
+            return Err("--sharpen-radius and --sharpen-threshold require sharpening (--sharpen or a sharpening profile)".to_string());
+        };
+        if let Some(radius) = cli.sharpen_radius {
+            sharpen.radius = radius;
+        }
+        if let Some(threshold) = cli.sharpen_threshold {
+            sharpen.threshold = threshold;
+        }
+    }
+    if let Some(score) = cli.min_ssim {
+        profile.min_similarity = Some(SimilarityTarget::Ssim(score));
+    }
+    if let Some(score) = cli.min_ms_ssim {
+        profile.min_similarity = Some(SimilarityTarget::MsSsim(score));
+    }
+
+    if cli.webp_lossless {
+        profile.webp.lossless = true;
+    }
+    if let Some(method) = cli.webp_method {
+        profile.webp.method = method;
+    }
+    if let Some(level) = cli.webp_near_lossless {
+        profile.webp.lossless = true;
+        profile.webp.near_lossless = Some(level);
+    }
+
+    if cli.poster {
+        profile.poster = true;
+    }
+    if cli.no_auto_orient {
+        profile.auto_orient = false;
+    }
+    if let Some(policy) = cli.metadata {
+        profile.metadata = policy.into();
+    }
+    if let Some(color_space) = cli.color_space {
+        profile.color_space = color_space.into();
+    }
+
+    if let Some(speed) = cli.avif_speed {
+        profile.avif.speed = speed;
+    }
+    if let Some(subsampling) = cli.avif_subsampling {
+        profile.avif.subsampling = subsampling.into();
+    }
+
+    profile.validate().map_err(|e| e.to_string())?;
+    Ok(profile)
 }
 
 /// Run batch processing
@@ -343,9 +846,10 @@ async fn run_batch_processing(
     cli: &Cli,
     input_path: &std::path::Path,
     output_path: &std::path::Path,
-    resize_config: &ResizeConfig,
-    _config: &Option<Config>,
+    profile: &ProcessingProfile,
+    config: &Option<Config>,
 ) -> Result<BatchResults, Box<dyn std::error::Error>> {
+    let resize_config = ResizeConfig::from(profile);
     
     info!("Starting batch processing");
     info!("Input: {:?}", input_path);
@@ -362,15 +866,35 @@ async fn run_batch_processing(
     info!("Found {} files to process", files.len());
 
     if cli.dry_run {
-        println!("{} files would be processed:", style(files.len()).bold());
-        for file in &files {
-            println!("  {}", file.display());
+        if cli.json {
+            for file in &files {
+                print_json_line(&DryRunFile { input_path: file });
+            }
+        } else {
+            println!("{} files would be processed:", style(files.len()).bold());
+            for file in &files {
+                println!("  {}", file.display());
+            }
         }
         return Ok(BatchResults::default());
     }
 
-    // Create processing engine
-    let engine = ProcessingEngine::new();
+    // CLI flag wins over the configuration file
+    let threads = cli.threads
+        .or_else(|| config.as_ref().and_then(|c| c.processing.threads));
+    if threads == Some(0) {
+        return Err("Thread count must be greater than 0".into());
+    }
+
+    // Outputs mirror the input directory structure
+    let input_root = if input_path.is_dir() {
+        input_path
+    } else {
+        input_path.parent().unwrap_or(input_path)
+    };
+    let processor = ParallelProcessor::new(threads)
+        .with_input_root(input_root)
+        .with_naming(profile.naming.clone());
 
     // Set up progress bar
     let progress = if !cli.json && !cli.quiet {
@@ -385,68 +909,96 @@ async fn run_batch_processing(
         None
     };
 
-    // Process files
-    let mut results = BatchResults::default();
-    
-    for (_index, file_path) in files.iter().enumerate() {
-        let output_file_path = generate_output_path(file_path, input_path, output_path, resize_config);
-        
-        if let Some(pb) = &progress {
-            pb.set_message(format!("Processing: {}", file_path.file_name().unwrap_or_default().to_string_lossy()));
-        }
-
-        let _file_start = Instant::now();
-        match engine.process_file(file_path, &output_file_path, resize_config).await {
-            Ok(result) => {
-                results.successful += 1;
-                results.total_input_size += result.original_info.file_size;
-                results.total_output_size += result.output_info.file_size;
-                
-                // Delete original file if requested and processing was successful
-                if cli.delete_originals {
-                    // Only delete if the output is different from input
-                    if file_path != &output_file_path {
-                        match tokio::fs::remove_file(file_path).await {
-                            Ok(_) => {
-                                debug!("Deleted original file: {}", file_path.display());
-                            }
-                            Err(e) => {
-                                warn!("Failed to delete original file {}: {}", file_path.display(), e);
-                            }
-                        }
-                    } else {
-                        debug!("Skipping deletion: input and output paths are the same");
-                    }
-                }
-                
-                if cli.json {
-                    // Note: ProcessingResult needs Serialize trait
-                    debug!("JSON output requested for successful file");
-                }
+    let reporter = tokio::spawn(report_progress(processor.subscribe_lossless(), progress.clone(), cli.json));
+
+    let batch = processor
+        .process_with_strategy(files, output_path, &resize_config, cli.strategy.into())
+        .await;
+
+    // Dropping the processor closes the update channel so the reporter always finishes
+    drop(processor);
+    let _ = reporter.await;
+    let batch = batch?;
+
+    if let Some(pb) = &progress {
+        pb.finish_with_message("Processing complete");
+    }
+
+    // Delete original files only after their output was written successfully
+    if cli.delete_originals {
+        for result in &batch.successful_results {
+            if result.input_path == result.output_path {
+                debug!("Skipping deletion: input and output paths are the same");
+                continue;
             }
-            Err(e) => {
-                results.failed += 1;
-                let error_msg = format!("Failed to process {}: {}", file_path.display(), e);
-                
-                if cli.json {
-                    // Note: ProcessingResult needs Serialize trait
-                    debug!("JSON output requested for failed file");
-                } else {
-                    warn!("{}", error_msg);
+            match tokio::fs::remove_file(&result.input_path).await {
+                Ok(_) => {
+                    debug!("Deleted original file: {}", result.input_path.display());
+                }
+                Err(e) => {
+                    warn!("Failed to delete original file {}: {}", result.input_path.display(), e);
                 }
             }
         }
+    }
+
+    Ok(BatchResults {
+        successful: batch.successful,
+        failed: batch.failed,
+        total_input_size: batch.total_input_size,
+        total_output_size: batch.total_output_size,
+        ..Default::default()
+    })
+}
 
-        if let Some(pb) = &progress {
-            pb.inc(1);
+/// Forward progress updates from the processor to the progress bar or JSON stream
+async fn report_progress(
+    mut receiver: tokio::sync::mpsc::UnboundedReceiver<ProgressUpdate>,
+    progress: Option<ProgressBar>,
+    json_output: bool,
+) {
+    // Lossless: CI reads every error and file_completed event from the JSON stream
+    while let Some(update) = receiver.recv().await {
+        // The final summary line replaces the batch-completed event
+        if json_output && !matches!(update, ProgressUpdate::BatchCompleted { .. }) {
+            print_json_line(&update);
         }
-    }
 
-    if let Some(pb) = &progress {
-        pb.finish_with_message("Processing complete");
+        match update {
+            ProgressUpdate::FileStarted { filename } => {
+                if let Some(pb) = &progress {
+                    let name = std::path::Path::new(&filename)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or(filename);
+                    pb.set_message(format!("Processing: {}", name));
+                }
+            }
+            ProgressUpdate::FileCompleted { .. } => {
+                if let Some(pb) = &progress {
+                    pb.inc(1);
+                }
+            }
+            ProgressUpdate::Error { filename, error } => {
+                if !json_output {
+                    warn!("Failed to process {}: {}", filename, error);
+                }
+                if let Some(pb) = &progress {
+                    pb.inc(1);
+                }
+            }
+            ProgressUpdate::BatchCompleted { .. } => break,
+            ProgressUpdate::Started { .. } => {}
+        }
     }
+}
 
-    Ok(results)
+/// Print a value as a single JSON line on stdout
+fn print_json_line<T: serde::Serialize>(value: &T) {
+    match serde_json::to_string(value) {
+        Ok(line) => println!("{}", line),
+        Err(e) => error!("Failed to serialize JSON output: {}", e),
+    }
 }
 
 /// Discover input files
@@ -490,34 +1042,54 @@ async fn discover_files(
     Ok(files)
 }
 
-/// Generate output file path
-fn generate_output_path(
-    input_file: &std::path::Path,
-    input_root: &std::path::Path,
-    output_root: &std::path::Path,
-    resize_config: &ResizeConfig,
-) -> PathBuf {
-    let relative_path = input_file.strip_prefix(input_root).unwrap_or(input_file);
-    let mut output_path = output_root.join(relative_path);
-    
-    // Change extension if format conversion is specified
-    if let Some(format) = resize_config.format {
-        output_path.set_extension(format.extension());
+/// Run watch mode
+#[cfg(feature = "automation")]
+async fn run_watch_mode(
+    input_path: &std::path::Path,
+    output_path: &std::path::Path,
+    profile: &ProcessingProfile,
+    config: &Option<Config>,
+    recursive: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use fastresize::automation::{FileWatcher, WatchTarget};
+    use fastresize::config::AutomationConfig;
+
+    if !input_path.is_dir() {
+        return Err(format!("Watch mode requires an input directory: {}", input_path.display()).into());
     }
-    
-    output_path
+
+    let mut watcher = match config {
+        Some(config) => FileWatcher::from_config(config)?,
+        None => FileWatcher::new(std::time::Duration::from_millis(
+            AutomationConfig::default().watch_interval,
+        )),
+    };
+
+    watcher.add_target(WatchTarget::new(
+        input_path.to_path_buf(),
+        output_path.to_path_buf(),
+        profile.clone(),
+        recursive,
+    ));
+
+    println!("{}: Watching {} folder(s), press Ctrl+C to stop",
+             style("Info").blue().bold(),
+             watcher.targets().len());
+
+    watcher.run().await?;
+    Ok(())
 }
 
 /// Run watch mode
+#[cfg(not(feature = "automation"))]
 async fn run_watch_mode(
     _input_path: &std::path::Path,
     _output_path: &std::path::Path,
-    _resize_config: &ResizeConfig,
+    _profile: &ProcessingProfile,
     _config: &Option<Config>,
+    _recursive: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // TODO: Implement watch mode
-    println!("{}: Watch mode not yet implemented", style("Info").blue().bold());
-    Ok(())
+    Err("Watch mode requires the 'automation' feature".into())
 }
 
 /// Show available profiles
@@ -525,18 +1097,13 @@ fn show_profiles(detailed: bool) {
     println!("{}", style("Available Processing Profiles:").bold());
     println!();
 
-    // For now, show basic profile information
-    let profile_info = vec![
-        ("thumbnail", "Square thumbnails (150x150, 85% quality)"),
-        ("web", "Web optimized (1920px width, 80% quality)"),
-        ("mobile", "Mobile friendly (800px width, 75% quality)"),
-        ("print", "High quality for print (300 DPI, 95% quality)"),
-    ];
-    
-    for (name, description) in &profile_info {
+    let mut profiles: Vec<_> = Profiles::all().into_iter().collect();
+    profiles.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, profile) in &profiles {
         println!("{}", style(name).cyan().bold());
         if detailed {
-            println!("  {}", description);
+            println!("  {}", describe_profile(profile));
             println!();
         }
     }
@@ -547,6 +1114,38 @@ fn show_profiles(detailed: bool) {
     }
 }
 
+/// One-line summary of a profile: resize mode, quality, output format and DPI
+fn describe_profile(profile: &ProcessingProfile) -> String {
+    let resize = match &profile.resize_mode {
+        ResizeMode::Scale { factor } => format!("scale {}x", factor),
+        ResizeMode::Width { width } => format!("{}px wide", width),
+        ResizeMode::Height { height } => format!("{}px high", height),
+        ResizeMode::LongEdge { px } => format!("{}px long edge", px),
+        ResizeMode::ShortEdge { px } => format!("{}px short edge", px),
+        ResizeMode::MaxPixels { megapixels } => format!("at most {} megapixels", megapixels),
+        ResizeMode::Fit { width, height } => format!("fit {}x{}", width, height),
+        ResizeMode::Pad { width, height, background, .. } => {
+            format!("pad to {}x{} on {}", width, height, background)
+        }
+        ResizeMode::Physical { width, height, unit, .. } => {
+            let unit = match unit {
+                LengthUnit::Millimeter => "mm",
+                LengthUnit::Inch => "in",
+            };
+            format!("fit {}x{}{}", width, height, unit)
+        }
+        ResizeMode::Fill { width, height, .. } => format!("fill {}x{}", width, height),
+    };
+    let format = profile.format
+        .map_or_else(|| "original format".to_string(), |format| format.extension().to_uppercase());
+
+    let mut description = format!("{}, quality {}, {}", resize, profile.quality, format);
+    if let Some(dpi) = profile.resize_mode.dpi() {
+        description.push_str(&format!(", {} DPI", dpi));
+    }
+    description
+}
+
 /// Validate configuration file
 fn validate_config_file(file_path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
     let config = Config::from_file(file_path)?;
@@ -585,7 +1184,7 @@ async fn show_system_info() {
     
     // Version information
     println!("{}: {}", style("Version").bold(), env!("CARGO_PKG_VERSION"));
-    println!("{}: {}", style("Build").bold(), "Release"); // TODO: Add build info
+    println!("{}: Release", style("Build").bold()); // TODO: Add build info
     println!();
     
     // System information
@@ -611,7 +1210,7 @@ async fn show_system_info() {
     // Supported formats
     println!("{}", style("Supported Formats:").bold());
     println!("  Input: JPEG, PNG, WebP, GIF, TIFF, BMP");
-    println!("  Output: JPEG, PNG, WebP, GIF, TIFF, BMP");
+    println!("  Output: JPEG, PNG, WebP, GIF, TIFF, BMP, AVIF");
     println!();
     
     // Feature support
@@ -620,25 +1219,95 @@ async fn show_system_info() {
     println!("  ✓ Memory optimization");
     println!("  ✓ Large file support");
     println!("  ✓ Format conversion");
-    println!("  - Watch mode (coming soon)");
+    if cfg!(feature = "automation") {
+        println!("  ✓ Watch mode");
+    } else {
+        println!("  - Watch mode (disabled)");
+    }
     println!("  - GPU acceleration (experimental)");
 }
 
 /// Run performance benchmark
 async fn run_benchmark(
-    _size: &str,
-    _iterations: u32,
+    size: &str,
+    iterations: u32,
+    formats: Vec<CliImageFormat>,
+    output: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    threshold: f64,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // TODO: Implement benchmarking
-    println!("{}: Benchmarking not yet implemented", style("Info").blue().bold());
+    use fastresize::processing::benchmark::{Benchmark, BenchmarkConfig, BenchmarkReport, BenchmarkSize};
+
+    let sizes = if size.eq_ignore_ascii_case("all") {
+        BenchmarkSize::all().to_vec()
+    } else {
+        size.split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<BenchmarkSize>, _>>()?
+    };
+
+    let mut config = BenchmarkConfig::new()
+        .sizes(sizes)
+        .iterations(iterations);
+    if !formats.is_empty() {
+        config = config.formats(formats.into_iter().map(Into::into).collect());
+    }
+
+    // Load the baseline up front so a bad path fails before the long run
+    let baseline = baseline.map(BenchmarkReport::from_file).transpose()?;
+
+    println!("{}", style("FastResize Benchmark").bold());
+    println!("{} iterations per measurement, median / p95 in milliseconds", iterations);
+    println!();
+    println!("  {:<8} {:<8} {:<12} {:>10} {:>10}", "Size", "Phase", "Variant", "Median", "P95");
+
+    let report = tokio::task::spawn_blocking(move || {
+        Benchmark::new(config).run(|result| {
+            println!("  {:<8} {:<8} {:<12} {:>10.2} {:>10.2}",
+                     result.size.name(), result.phase.name(), result.variant,
+                     result.median_ms, result.p95_ms);
+        })
+    }).await??;
+
+    if let Some(path) = &output {
+        report.to_file(path)?;
+        println!();
+        println!("{}: Results written to {}", style("Success").green().bold(), path.display());
+    }
+
+    if let Some(baseline) = baseline {
+        let comparisons = report.compare(&baseline, threshold);
+
+        println!();
+        println!("{} (threshold {:.1}%)", style("Baseline Comparison:").bold(), threshold);
+        for comparison in &comparisons {
+            let change = format!("{:+.1}%", comparison.change_percent);
+            let change = if comparison.regressed {
+                style(change).red().bold()
+            } else if comparison.change_percent < -threshold {
+                style(change).green()
+            } else {
+                style(change).dim()
+            };
+            println!("  {:<32} {:>10.2} → {:>10.2} {:>9}",
+                     comparison.key, comparison.baseline_median_ms,
+                     comparison.current_median_ms, change);
+        }
+
+        let regressions = comparisons.iter().filter(|c| c.regressed).count();
+        if regressions > 0 {
+            return Err(format!("{} measurement(s) regressed by more than {:.1}%", regressions, threshold).into());
+        }
+        println!("{}: No regressions against baseline", style("Success").green().bold());
+    }
+
     Ok(())
 }
 
 /// Print processing summary
 fn print_summary(results: &BatchResults, duration: std::time::Duration, json_output: bool) {
     if json_output {
-        // Note: BatchResults needs Serialize trait for full JSON support
-        debug!("JSON output requested for summary");
+        print_json_line(results);
         return;
     }
 
@@ -668,10 +1337,23 @@ fn print_summary(results: &BatchResults, duration: std::time::Duration, json_out
 }
 
 /// Batch processing results
+///
+/// Serialized as the final `{"event":"summary",...}` line of the JSON stream.
 #[derive(Default, Debug, serde::Serialize)]
+#[serde(tag = "event", rename = "summary")]
 struct BatchResults {
     successful: u32,
     failed: u32,
     total_input_size: u64,
     total_output_size: u64,
-}
\ No newline at end of file
+    duration_secs: f64,
+}
+
+/// A file a dry run would process
+///
+/// Serialized as one `{"event":"dry_run",...}` line of the JSON stream per file.
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "event", rename = "dry_run")]
+struct DryRunFile<'a> {
+    input_path: &'a std::path::Path,
+}
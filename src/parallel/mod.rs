@@ -7,7 +7,7 @@ use tokio::sync::Semaphore;
 use tracing::{info, debug};
 use rayon::prelude::*;
 
-use crate::config::ResizeConfig;
+use crate::config::{NamingConfig, ResizeConfig};
 use crate::processing::{ProcessingEngine, ProcessingResult};
 use crate::error::{Result, FastResizeError};
 
@@ -23,6 +23,8 @@ pub struct ParallelProcessor {
     max_concurrent: usize,
     progress_tracker: Arc<ProgressTracker>,
     semaphore: Arc<Semaphore>,
+    input_root: Option<PathBuf>,
+    naming: Option<NamingConfig>,
 }
 
 impl ParallelProcessor {
@@ -31,7 +33,7 @@ impl ParallelProcessor {
         let max_concurrent = max_concurrent.unwrap_or_else(|| {
             // Use number of logical CPUs, but cap at 16 to avoid excessive memory usage
             num_cpus::get().min(16)
-        });
+        }).max(1);
 
         info!("Initializing parallel processor with {} concurrent workers", max_concurrent);
 
@@ -40,6 +42,53 @@ impl ParallelProcessor {
             max_concurrent,
             progress_tracker: Arc::new(ProgressTracker::new()),
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            input_root: None,
+            naming: None,
+        }
+    }
+
+    /// Mirror each file's location below `root` in the output directory
+    ///
+    /// Without an input root all outputs are written flat into the output directory.
+    pub fn with_input_root<P: Into<PathBuf>>(mut self, root: P) -> Self {
+        self.input_root = Some(root.into());
+        self
+    }
+
+    /// Name output files using a profile's naming configuration
+    ///
+    /// Without naming configuration outputs keep the input filename.
+    pub fn with_naming(mut self, naming: NamingConfig) -> Self {
+        self.naming = Some(naming);
+        self
+    }
+
+    /// Number of files processed concurrently
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Process a batch of files using the given strategy
+    pub async fn process_with_strategy(
+        &self,
+        files: Vec<PathBuf>,
+        output_dir: &std::path::Path,
+        config: &ResizeConfig,
+        strategy: ProcessingStrategy,
+    ) -> Result<BatchProcessingResult> {
+        let strategy = strategy.resolve(files.len());
+        info!("Using {:?} processing strategy", strategy);
+
+        match strategy {
+            ProcessingStrategy::Async | ProcessingStrategy::Auto => {
+                self.process_batch(files, output_dir, config).await
+            }
+            ProcessingStrategy::CpuIntensive => {
+                self.process_batch_cpu_pool(files, output_dir, config).await
+            }
+            ProcessingStrategy::Hybrid => {
+                self.process_batch_hybrid(files, output_dir, config).await
+            }
         }
     }
 
@@ -67,6 +116,7 @@ impl ParallelProcessor {
 
         let processing_time = start_time.elapsed();
         let batch_result = self.aggregate_results(results, processing_time);
+        self.progress_tracker.complete_batch();
         
         info!("Parallel processing completed in {:.2}s", processing_time.as_secs_f64());
         
@@ -74,6 +124,9 @@ impl ParallelProcessor {
     }
 
     /// Process files using CPU-bound thread pool
+    ///
+    /// Blocks the calling thread until the batch is done. Must be called from
+    /// within a Tokio runtime, which drives the per-file I/O.
     pub fn process_batch_cpu_intensive(
         &self,
         files: Vec<PathBuf>,
@@ -84,29 +137,52 @@ impl ParallelProcessor {
         let total_files = files.len();
         
         info!("Starting CPU-intensive parallel processing of {} files", total_files);
-        
-        // Use rayon for CPU-bound parallelism
-        let results: Vec<Result<ProcessingResult>> = files
-            .par_iter()
-            .map(|file_path| {
-                let output_path = self.generate_output_path(file_path, output_dir, config);
-                
-                // Use blocking runtime for CPU-intensive work
-                let runtime = tokio::runtime::Handle::current();
-                runtime.block_on(async {
-                    self.process_single_file(file_path, &output_path, config).await
-                })
-            })
-            .collect();
+        self.progress_tracker.start(total_files as u64);
+
+        let pool = self.build_thread_pool()?;
+        let jobs = self.cpu_jobs(files, output_dir, config);
+        let results = pool.install(|| jobs.run());
 
         let processing_time = start_time.elapsed();
         let batch_result = self.aggregate_results(results, processing_time);
+        self.progress_tracker.complete_batch();
         
         info!("CPU-intensive processing completed in {:.2}s", processing_time.as_secs_f64());
         
         Ok(batch_result)
     }
 
+    /// Run the CPU-bound thread pool without blocking the async runtime
+    async fn process_batch_cpu_pool(
+        &self,
+        files: Vec<PathBuf>,
+        output_dir: &std::path::Path,
+        config: &ResizeConfig,
+    ) -> Result<BatchProcessingResult> {
+        let start_time = Instant::now();
+        let total_files = files.len();
+
+        info!("Starting CPU-intensive parallel processing of {} files", total_files);
+        self.progress_tracker.start(total_files as u64);
+
+        let pool = self.build_thread_pool()?;
+        let jobs = self.cpu_jobs(files, output_dir, config);
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        pool.spawn(move || {
+            let _ = sender.send(jobs.run());
+        });
+        let results = receiver.await
+            .map_err(|_| FastResizeError::parallel("CPU worker pool stopped unexpectedly"))?;
+
+        let processing_time = start_time.elapsed();
+        let batch_result = self.aggregate_results(results, processing_time);
+        self.progress_tracker.complete_batch();
+
+        info!("CPU-intensive processing completed in {:.2}s", processing_time.as_secs_f64());
+
+        Ok(batch_result)
+    }
+
     /// Process files with hybrid approach (I/O async, CPU parallel)
     pub async fn process_batch_hybrid(
         &self,
@@ -118,9 +194,10 @@ impl ParallelProcessor {
         let total_files = files.len();
         
         info!("Starting hybrid parallel processing of {} files", total_files);
+        self.progress_tracker.start(total_files as u64);
         
         // Split into chunks for better memory management
-        let chunk_size = (total_files / self.max_concurrent).max(1).min(10);
+        let chunk_size = (total_files / self.max_concurrent).clamp(1, 10);
         let chunks: Vec<_> = files.chunks(chunk_size).collect();
         
         let mut all_results = Vec::new();
@@ -141,6 +218,7 @@ impl ParallelProcessor {
 
         let processing_time = start_time.elapsed();
         let batch_result = self.aggregate_results(all_results, processing_time);
+        self.progress_tracker.complete_batch();
         
         info!("Hybrid processing completed in {:.2}s", processing_time.as_secs_f64());
         
@@ -167,25 +245,7 @@ impl ParallelProcessor {
                 // Acquire semaphore permit
                 let _permit = semaphore.acquire().await.unwrap();
                 
-                // Update progress
-                progress_tracker.start_file(file_path.file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string());
-                
-                // Process file
-                let result = engine.process_file(&file_path, &output_path, &config).await;
-                
-                // Update progress
-                match &result {
-                    Ok(_) => progress_tracker.complete_file(true),
-                    Err(e) => {
-                        progress_tracker.complete_file(false);
-                        debug!("Failed to process {:?}: {}", file_path, e);
-                    }
-                }
-                
-                result
+                process_tracked(&engine, &progress_tracker, &file_path, &output_path, &config).await
             });
             
             tasks.push(task);
@@ -213,12 +273,13 @@ impl ParallelProcessor {
         
         for file_path in chunk {
             let engine = Arc::clone(&self.engine);
+            let progress_tracker = Arc::clone(&self.progress_tracker);
             let output_path = self.generate_output_path(file_path, output_dir, config);
             let config = config.clone();
             let file_path = file_path.clone();
             
             let task = tokio::spawn(async move {
-                engine.process_file(&file_path, &output_path, &config).await
+                process_tracked(&engine, &progress_tracker, &file_path, &output_path, &config).await
             });
             
             tasks.push(task);
@@ -234,14 +295,37 @@ impl ParallelProcessor {
             .collect()
     }
 
-    /// Process a single file (used by parallel executors)
-    async fn process_single_file(
+    /// Build a rayon pool sized to the configured concurrency
+    fn build_thread_pool(&self) -> Result<rayon::ThreadPool> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.max_concurrent)
+            .thread_name(|index| format!("fastresize-worker-{}", index))
+            .build()
+            .map_err(|e| FastResizeError::parallel(format!("Failed to create thread pool: {}", e)))
+    }
+
+    /// Prepare a batch for the CPU-bound thread pool
+    fn cpu_jobs(
         &self,
-        input_path: &std::path::Path,
-        output_path: &std::path::Path,
+        files: Vec<PathBuf>,
+        output_dir: &std::path::Path,
         config: &ResizeConfig,
-    ) -> Result<ProcessingResult> {
-        self.engine.process_file(input_path, output_path, config).await
+    ) -> CpuJobs {
+        let files = files.into_iter()
+            .map(|file_path| {
+                let output_path = self.generate_output_path(&file_path, output_dir, config);
+                (file_path, output_path)
+            })
+            .collect();
+
+        CpuJobs {
+            // Rayon workers are outside the runtime, so capture its handle here
+            runtime: tokio::runtime::Handle::current(),
+            engine: Arc::clone(&self.engine),
+            progress_tracker: Arc::clone(&self.progress_tracker),
+            config: config.clone(),
+            files,
+        }
     }
 
     /// Generate output path for a file
@@ -251,11 +335,19 @@ impl ParallelProcessor {
         output_dir: &std::path::Path,
         config: &ResizeConfig,
     ) -> PathBuf {
-        let file_name = input_path.file_name().unwrap();
-        let mut output_path = output_dir.join(file_name);
+        let relative_path = self.input_root.as_ref()
+            .and_then(|root| input_path.strip_prefix(root).ok())
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or_else(|| std::path::Path::new(input_path.file_name().unwrap()));
+        let mut output_path = output_dir.join(relative_path);
         
-        // Change extension if format conversion is specified
-        if let Some(format) = config.format {
+        if let Some(naming) = &self.naming {
+            if let Some(file_name) = output_path.file_name().and_then(|name| name.to_str()) {
+                let new_name = naming.generate_filename(file_name, config.format);
+                output_path.set_file_name(new_name);
+            }
+        } else if let Some(format) = config.format {
+            // Change extension if format conversion is specified
             output_path.set_extension(format.extension());
         }
         
@@ -307,6 +399,67 @@ impl ParallelProcessor {
     pub fn get_progress(&self) -> ProgressState {
         self.progress_tracker.get_state()
     }
+
+    /// Subscribe to per-file progress updates
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<ProgressUpdate> {
+        self.progress_tracker.subscribe()
+    }
+
+    /// Subscribe to per-file progress updates without dropping any
+    pub fn subscribe_lossless(&self) -> tokio::sync::mpsc::UnboundedReceiver<ProgressUpdate> {
+        self.progress_tracker.subscribe_lossless()
+    }
+}
+
+/// A batch prepared for the rayon thread pool
+struct CpuJobs {
+    runtime: tokio::runtime::Handle,
+    engine: Arc<ProcessingEngine>,
+    progress_tracker: Arc<ProgressTracker>,
+    config: ResizeConfig,
+    files: Vec<(PathBuf, PathBuf)>,
+}
+
+impl CpuJobs {
+    /// Process all files on the current rayon pool
+    fn run(&self) -> Vec<Result<ProcessingResult>> {
+        self.files
+            .par_iter()
+            .map(|(input_path, output_path)| {
+                self.runtime.block_on(process_tracked(
+                    &self.engine,
+                    &self.progress_tracker,
+                    input_path,
+                    output_path,
+                    &self.config,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Process a single file and report its progress
+async fn process_tracked(
+    engine: &ProcessingEngine,
+    progress_tracker: &ProgressTracker,
+    input_path: &std::path::Path,
+    output_path: &std::path::Path,
+    config: &ResizeConfig,
+) -> Result<ProcessingResult> {
+    let filename = input_path.display().to_string();
+    progress_tracker.start_file(filename.clone());
+
+    let result = engine.process_file(input_path, output_path, config).await;
+
+    match &result {
+        Ok(processing_result) => progress_tracker.complete_with_result(filename, processing_result),
+        Err(e) => {
+            debug!("Failed to process {:?}: {}", input_path, e);
+            progress_tracker.report_error(filename, e.user_message());
+        }
+    }
+
+    result
 }
 
 /// Result of batch processing operation
@@ -405,6 +558,20 @@ impl ProcessingStrategy {
             Self::Hybrid
         }
     }
+
+    /// Resolve `Auto` into a concrete strategy for this batch
+    pub fn resolve(self, file_count: usize) -> Self {
+        match self {
+            Self::Auto => {
+                use sysinfo::{System, SystemExt};
+
+                let mut system = System::new();
+                system.refresh_memory();
+                Self::choose_auto(file_count, system.available_memory())
+            }
+            strategy => strategy,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -430,7 +597,7 @@ mod tests {
         assert!(matches!(strategy, ProcessingStrategy::Async));
 
         // Low memory
-        let strategy = ProcessingStrategy::choose_auto(50, 1 * 1024 * 1024 * 1024);
+        let strategy = ProcessingStrategy::choose_auto(50, 1024 * 1024 * 1024);
         assert!(matches!(strategy, ProcessingStrategy::Hybrid));
 
         // Large batch
@@ -465,6 +632,7 @@ mod tests {
             mode: ResizeMode::Scale { factor: 0.5 },
             quality: 90,
             format: Some(crate::config::ImageFormat::WebP),
+            ..ResizeConfig::new()
         };
 
         let input_path = std::path::Path::new("test.jpg");
@@ -474,4 +642,110 @@ mod tests {
         assert_eq!(output_path.extension().unwrap(), "webp");
         assert_eq!(output_path.file_stem().unwrap(), "test");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_output_path_with_input_root() {
+        let processor = ParallelProcessor::new(Some(1)).with_input_root("/input");
+        let config = ResizeConfig::new();
+
+        let output_path = processor.generate_output_path(
+            std::path::Path::new("/input/sub/test.jpg"),
+            std::path::Path::new("/output"),
+            &config,
+        );
+        assert_eq!(output_path, PathBuf::from("/output/sub/test.jpg"));
+
+        // Files outside the root fall back to a flat layout
+        let output_path = processor.generate_output_path(
+            std::path::Path::new("/elsewhere/test.jpg"),
+            std::path::Path::new("/output"),
+            &config,
+        );
+        assert_eq!(output_path, PathBuf::from("/output/test.jpg"));
+    }
+
+    #[tokio::test]
+    async fn test_output_path_with_naming() {
+        let processor = ParallelProcessor::new(Some(1))
+            .with_input_root("/input")
+            .with_naming(NamingConfig {
+                prefix: Some("web_".to_string()),
+                ..Default::default()
+            });
+        let config = ResizeConfig::new().format(crate::config::ImageFormat::WebP);
+
+        let output_path = processor.generate_output_path(
+            std::path::Path::new("/input/sub/photo.jpg"),
+            std::path::Path::new("/output"),
+            &config,
+        );
+        assert_eq!(output_path, PathBuf::from("/output/sub/web_photo_resized.webp"));
+    }
+
+    #[tokio::test]
+    async fn test_process_with_each_strategy() {
+        let input_dir = TempDir::new().unwrap();
+        let files: Vec<PathBuf> = (0..3)
+            .map(|i| {
+                let path = input_dir.path().join(format!("image{}.png", i));
+                image::RgbImage::from_pixel(20, 10, image::Rgb([10, 20, 30]))
+                    .save(&path)
+                    .unwrap();
+                path
+            })
+            .collect();
+
+        let config = ResizeConfig::new().mode(ResizeMode::Width { width: 10 });
+        let strategies = [
+            ProcessingStrategy::Async,
+            ProcessingStrategy::CpuIntensive,
+            ProcessingStrategy::Hybrid,
+            ProcessingStrategy::Auto,
+        ];
+
+        for strategy in strategies {
+            let output_dir = TempDir::new().unwrap();
+            let processor = ParallelProcessor::new(Some(2));
+            let mut receiver = processor.subscribe();
+            let mut lossless = processor.subscribe_lossless();
+
+            let result = processor
+                .process_with_strategy(files.clone(), output_dir.path(), &config, strategy)
+                .await
+                .unwrap();
+
+            assert_eq!(result.successful, 3, "strategy {:?}", strategy);
+            assert_eq!(result.failed, 0);
+            for i in 0..3 {
+                assert!(output_dir.path().join(format!("image{}.png", i)).exists());
+            }
+
+            let mut completed = 0;
+            while let Ok(update) = receiver.try_recv() {
+                if matches!(update, ProgressUpdate::FileCompleted { success: true, .. }) {
+                    completed += 1;
+                }
+            }
+            assert_eq!(completed, 3, "strategy {:?}", strategy);
+
+            let mut results = 0;
+            while let Ok(update) = lossless.try_recv() {
+                if let ProgressUpdate::FileCompleted { result: Some(result), .. } = update {
+                    assert_eq!(result.output_info.width, 10);
+                    assert!(result.output_path.starts_with(output_dir.path()));
+                    results += 1;
+                }
+            }
+            assert_eq!(results, 3, "strategy {:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_strategy_resolve_keeps_explicit_choice() {
+        assert!(matches!(
+            ProcessingStrategy::CpuIntensive.resolve(1),
+            ProcessingStrategy::CpuIntensive
+        ));
+        assert!(!matches!(ProcessingStrategy::Auto.resolve(1), ProcessingStrategy::Auto));
+    }
+}
@@ -3,13 +3,18 @@
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::broadcast;
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, info};
 
+use crate::processing::{serialize_duration_secs, serialize_optional_duration_secs, ProcessingResult};
+
 /// Thread-safe progress tracker for parallel operations
 pub struct ProgressTracker {
     state: Arc<Mutex<ProgressState>>,
     sender: broadcast::Sender<ProgressUpdate>,
+    /// Unbounded subscribers that must see every update
+    lossless: Mutex<Vec<mpsc::UnboundedSender<ProgressUpdate>>>,
     start_time: Arc<Mutex<Option<Instant>>>,
     
     // Atomic counters for high-frequency updates
@@ -20,13 +25,15 @@ pub struct ProgressTracker {
 }
 
 /// Current progress state
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProgressState {
     pub total_files: u64,
     pub completed_files: usize,
     pub failed_files: usize,
     pub current_file: Option<String>,
+    #[serde(rename = "elapsed_secs", serialize_with = "serialize_duration_secs")]
     pub elapsed_time: Duration,
+    #[serde(rename = "estimated_remaining_secs", serialize_with = "serialize_optional_duration_secs")]
     pub estimated_remaining: Option<Duration>,
     pub bytes_processed: u64,
     pub pixels_processed: u64,
@@ -35,7 +42,10 @@ pub struct ProgressState {
 }
 
 /// Progress update event
-#[derive(Debug, Clone)]
+///
+/// Serializes as an object tagged with an `event` field, e.g. `{"event":"file_started",...}`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
 pub enum ProgressUpdate {
     Started {
         total_files: u64,
@@ -48,7 +58,11 @@ pub enum ProgressUpdate {
         success: bool,
         file_size: u64,
         pixels: u64,
+        #[serde(rename = "processing_time_secs", serialize_with = "serialize_duration_secs")]
         processing_time: Duration,
+        /// Full result, including output path and dimensions, when the file was processed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result: Option<Box<ProcessingResult>>,
     },
     BatchCompleted {
         final_state: ProgressState,
@@ -67,6 +81,7 @@ impl ProgressTracker {
         Self {
             state: Arc::new(Mutex::new(ProgressState::new())),
             sender,
+            lossless: Mutex::new(Vec::new()),
             start_time: Arc::new(Mutex::new(None)),
             completed: AtomicUsize::new(0),
             failed: AtomicUsize::new(0),
@@ -98,7 +113,7 @@ impl ProgressTracker {
         self.bytes_processed.store(0, Ordering::Relaxed);
         self.pixels_processed.store(0, Ordering::Relaxed);
 
-        let _ = self.sender.send(ProgressUpdate::Started { total_files });
+        self.send(ProgressUpdate::Started { total_files });
         
         info!("Started progress tracking for {} files", total_files);
     }
@@ -108,7 +123,7 @@ impl ProgressTracker {
         let mut state = self.state.lock().unwrap();
         state.current_file = Some(filename.clone());
         
-        let _ = self.sender.send(ProgressUpdate::FileStarted { filename });
+        self.send(ProgressUpdate::FileStarted { filename });
         
         debug!("Started processing file: {}", state.current_file.as_ref().unwrap());
     }
@@ -132,6 +147,18 @@ impl ProgressTracker {
             filename
         };
 
+        self.complete_named_file(filename, success, file_size, pixels, processing_time);
+    }
+
+    /// Mark a specific file as completed (safe when several files are in flight)
+    pub fn complete_named_file(
+        &self,
+        filename: String,
+        success: bool,
+        file_size: u64,
+        pixels: u64,
+        processing_time: Duration,
+    ) {
         if success {
             self.completed.fetch_add(1, Ordering::Relaxed);
             self.bytes_processed.fetch_add(file_size, Ordering::Relaxed);
@@ -143,15 +170,35 @@ impl ProgressTracker {
         // Update calculated fields
         self.update_state();
 
-        let _ = self.sender.send(ProgressUpdate::FileCompleted {
-            filename: filename.clone(),
+        debug!("Completed processing file: {} (success: {})", filename, success);
+
+        self.send(ProgressUpdate::FileCompleted {
+            filename,
             success,
             file_size,
             pixels,
             processing_time,
+            result: None,
         });
+    }
 
-        debug!("Completed processing file: {} (success: {})", filename, success);
+    /// Mark a file as completed, attaching its processing result to the update
+    pub fn complete_with_result(&self, filename: String, result: &ProcessingResult) {
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        self.bytes_processed.fetch_add(result.output_info.file_size, Ordering::Relaxed);
+        self.pixels_processed.fetch_add(result.original_info.pixel_count, Ordering::Relaxed);
+        self.update_state();
+
+        debug!("Completed processing file: {}", filename);
+
+        self.send(ProgressUpdate::FileCompleted {
+            filename,
+            success: true,
+            file_size: result.output_info.file_size,
+            pixels: result.original_info.pixel_count,
+            processing_time: result.processing_time,
+            result: Some(Box::new(result.clone())),
+        });
     }
 
     /// Report an error for a specific file
@@ -159,17 +206,15 @@ impl ProgressTracker {
         self.failed.fetch_add(1, Ordering::Relaxed);
         self.update_state();
         
-        let _ = self.sender.send(ProgressUpdate::Error { filename, error });
+        self.send(ProgressUpdate::Error { filename, error });
     }
 
     /// Update calculated state fields
     fn update_state(&self) {
-        let start_time = self.start_time.lock().unwrap();
-        if start_time.is_none() {
-            return;
-        }
-
-        let elapsed = start_time.unwrap().elapsed();
+        // Counters are kept current even before a batch has been started
+        let elapsed = self.start_time.lock().unwrap()
+            .map(|start| start.elapsed())
+            .unwrap_or_default();
         let completed = self.completed.load(Ordering::Relaxed);
         let failed = self.failed.load(Ordering::Relaxed);
         let bytes_processed = self.bytes_processed.load(Ordering::Relaxed);
@@ -207,16 +252,35 @@ impl ProgressTracker {
     }
 
     /// Subscribe to progress updates
+    ///
+    /// The channel is bounded: a receiver that falls more than 1000 updates
+    /// behind gets `RecvError::Lagged` and misses them.
     pub fn subscribe(&self) -> broadcast::Receiver<ProgressUpdate> {
         self.sender.subscribe()
     }
 
+    /// Subscribe to every progress update, without dropping any
+    ///
+    /// Updates queue up until received, so the receiver should be drained
+    /// for as long as the batch runs.
+    pub fn subscribe_lossless(&self) -> mpsc::UnboundedReceiver<ProgressUpdate> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.lossless.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Send an update to all subscribers
+    fn send(&self, update: ProgressUpdate) {
+        self.lossless.lock().unwrap().retain(|sender| sender.send(update.clone()).is_ok());
+        let _ = self.sender.send(update);
+    }
+
     /// Mark batch as completed
     pub fn complete_batch(&self) {
         self.update_state();
         let final_state = self.get_state();
         
-        let _ = self.sender.send(ProgressUpdate::BatchCompleted { 
+        self.send(ProgressUpdate::BatchCompleted { 
             final_state: final_state.clone() 
         });
         
@@ -373,7 +437,7 @@ impl ConsoleProgressReporter {
                         println!("Processing: {}", filename);
                     }
                 }
-                ProgressUpdate::FileCompleted { filename, success, file_size, pixels, processing_time } => {
+                ProgressUpdate::FileCompleted { filename, success, file_size, pixels, processing_time, .. } => {
                     if self.show_details {
                         if success {
                             println!("✓ {} ({:.2} MB, {:.0}K pixels, {:.2}s)", 
@@ -452,6 +516,34 @@ mod tests {
         assert!(matches!(update, ProgressUpdate::FileCompleted { success: true, .. }));
     }
 
+    #[tokio::test]
+    async fn test_lossless_subscription() {
+        let tracker = ProgressTracker::new();
+        let mut lagging = tracker.subscribe();
+        let mut lossless = tracker.subscribe_lossless();
+
+        tracker.start(2000);
+        for i in 0..2000 {
+            tracker.report_error(format!("{}.jpg", i), "Corrupted image".to_string());
+        }
+        tracker.complete_batch();
+
+        // The bounded channel drops the oldest updates, the lossless one keeps them all
+        assert!(matches!(
+            lagging.recv().await,
+            Err(broadcast::error::RecvError::Lagged(_))
+        ));
+        let mut errors = 0;
+        while let Some(update) = lossless.recv().await {
+            match update {
+                ProgressUpdate::Error { .. } => errors += 1,
+                ProgressUpdate::BatchCompleted { .. } => break,
+                _ => {}
+            }
+        }
+        assert_eq!(errors, 2000);
+    }
+
     #[test]
     fn test_progress_state_methods() {
         let mut state = ProgressState::new();
@@ -503,4 +595,28 @@ mod tests {
         let state = tracker.get_state();
         assert_eq!(state.failed_files, 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_progress_update_serialization() {
+        let update = ProgressUpdate::FileCompleted {
+            filename: "photo.jpg".to_string(),
+            success: true,
+            file_size: 2048,
+            pixels: 100,
+            processing_time: Duration::from_millis(250),
+            result: None,
+        };
+        let json = serde_json::to_string(&update).unwrap();
+        assert!(json.starts_with(r#"{"event":"file_completed""#));
+        assert!(json.contains(r#""processing_time_secs":0.25"#));
+        assert!(!json.contains(r#""result""#));
+
+        let update = ProgressUpdate::Error {
+            filename: "bad.jpg".to_string(),
+            error: "Corrupted image".to_string(),
+        };
+        let json: serde_json::Value = serde_json::to_value(&update).unwrap();
+        assert_eq!(json["event"], "error");
+        assert_eq!(json["filename"], "bad.jpg");
+    }
+}
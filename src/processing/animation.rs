@@ -0,0 +1,377 @@
+//! Frame-aware decoding and encoding for animated GIF and WebP
+
+use std::io::Cursor;
+
+use image::{AnimationDecoder, DynamicImage, RgbaImage};
+
+use crate::config::{ImageFormat, ResizeConfig};
+use crate::error::{Result, FastResizeError};
+use crate::processing::formats::{encoding_error, webp_config, webp_error};
+
+/// How a frame's area is cleared before the next frame is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameDisposal {
+    /// Leave the frame in place
+    Keep,
+    /// Clear to transparent
+    Background,
+    /// Restore what was there before the frame was drawn
+    Previous,
+}
+
+/// A fully composited animation frame
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    pub image: RgbaImage,
+    pub delay_ms: u32,
+    pub disposal: FrameDisposal,
+}
+
+/// A decoded multi-frame image
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub frames: Vec<AnimationFrame>,
+    /// Number of times to play, 0 = forever
+    pub loop_count: u16,
+}
+
+impl Animation {
+    /// Canvas size, taken from the first frame
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.frames
+            .first()
+            .map_or((0, 0), |frame| frame.image.dimensions())
+    }
+
+    /// Replace every frame image, keeping timing and disposal
+    pub fn map_frames<F>(self, mut f: F) -> Result<Self>
+    where
+        F: FnMut(DynamicImage) -> Result<DynamicImage>,
+    {
+        let frames = self.frames
+            .into_iter()
+            .map(|frame| {
+                let image = f(DynamicImage::ImageRgba8(frame.image))?.to_rgba8();
+                Ok(AnimationFrame { image, ..frame })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { frames, ..self })
+    }
+}
+
+/// Whether a format can hold more than one frame
+pub fn is_animated_format(format: ImageFormat) -> bool {
+    matches!(format, ImageFormat::Gif | ImageFormat::WebP)
+}
+
+/// Decode every frame of an animated GIF or WebP
+///
+/// Returns `None` for single-frame images and formats without animation.
+pub fn decode_animation(data: &[u8], format: ImageFormat) -> Result<Option<Animation>> {
+    let (frames, loop_count, disposals) = match format {
+        ImageFormat::Gif => {
+            let (loop_count, disposals) = gif_animation_info(data)?;
+            if disposals.len() < 2 {
+                return Ok(None);
+            }
+            let decoder = image::codecs::gif::GifDecoder::new(Cursor::new(data))?;
+            (decoder.into_frames().collect_frames()?, loop_count, disposals)
+        }
+        ImageFormat::WebP => {
+            let mut decoder = image::codecs::webp::WebPDecoder::new(Cursor::new(data))?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            // The ANIM background color is only a hint; players start transparent
+            decoder.set_background_color(image::Rgba([0, 0, 0, 0]))?;
+            let (loop_count, disposals) = webp_animation_info(data);
+            (decoder.into_frames().collect_frames()?, loop_count, disposals)
+        }
+        _ => return Ok(None),
+    };
+
+    if frames.len() < 2 {
+        return Ok(None);
+    }
+
+    let frames = frames
+        .into_iter()
+        .enumerate()
+        .map(|(index, frame)| {
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            AnimationFrame {
+                delay_ms: numerator / denominator.max(1),
+                disposal: disposals.get(index).copied().unwrap_or(FrameDisposal::Keep),
+                image: frame.into_buffer(),
+            }
+        })
+        .collect();
+
+    Ok(Some(Animation { frames, loop_count }))
+}
+
+/// Loop count and per-frame disposal of a GIF, without decoding pixels
+fn gif_animation_info(data: &[u8]) -> Result<(u16, Vec<FrameDisposal>)> {
+    let mut options = gif::DecodeOptions::new();
+    options.skip_frame_decoding(true);
+    let mut decoder = options.read_info(data).map_err(gif_decoding_error)?;
+
+    let mut disposals = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().map_err(gif_decoding_error)? {
+        disposals.push(match frame.dispose {
+            gif::DisposalMethod::Background => FrameDisposal::Background,
+            gif::DisposalMethod::Previous => FrameDisposal::Previous,
+            gif::DisposalMethod::Any | gif::DisposalMethod::Keep => FrameDisposal::Keep,
+        });
+    }
+
+    let loop_count = match decoder.repeat() {
+        gif::Repeat::Infinite => 0,
+        gif::Repeat::Finite(count) => count,
+    };
+    Ok((loop_count, disposals))
+}
+
+fn gif_decoding_error(error: gif::DecodingError) -> FastResizeError {
+    image::ImageError::Decoding(image::error::DecodingError::new(
+        image::error::ImageFormatHint::Exact(image::ImageFormat::Gif),
+        error,
+    ))
+    .into()
+}
+
+/// Loop count and per-frame disposal from the WebP ANIM and ANMF chunks
+fn webp_animation_info(data: &[u8]) -> (u16, Vec<FrameDisposal>) {
+    let mut loop_count = 0;
+    let mut disposals = Vec::new();
+
+    // Chunks follow the 12-byte RIFF header, padded to even sizes
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let fourcc = &data[offset..offset + 4];
+        let size = u32::from_le_bytes([data[offset + 4], data[offset + 5], data[offset + 6], data[offset + 7]]) as usize;
+        let body = &data[offset + 8..(offset + 8).saturating_add(size).min(data.len())];
+
+        match fourcc {
+            b"ANIM" if body.len() >= 6 => loop_count = u16::from_le_bytes([body[4], body[5]]),
+            b"ANMF" if body.len() >= 16 => disposals.push(if body[15] & 1 == 1 {
+                FrameDisposal::Background
+            } else {
+                FrameDisposal::Keep
+            }),
+            _ => {}
+        }
+
+        offset = offset.saturating_add(8 + size + size % 2);
+    }
+
+    (loop_count, disposals)
+}
+
+/// Encode an animation as GIF or animated WebP
+///
+/// GIF output keeps each frame's disposal method. For WebP, libwebp picks
+/// frame rectangles and disposal itself, which is safe because every frame
+/// is a full composited canvas.
+pub fn encode_animation(animation: &Animation, format: ImageFormat, config: &ResizeConfig) -> Result<Vec<u8>> {
+    match format {
+        ImageFormat::Gif => encode_gif_animation(animation),
+        ImageFormat::WebP => encode_webp_animation(animation, config),
+        _ => Err(FastResizeError::unsupported_format(
+            format!("{:?} (animated output)", format),
+            None,
+        )),
+    }
+}
+
+fn encode_gif_animation(animation: &Animation) -> Result<Vec<u8>> {
+    let (width, height) = animation.dimensions();
+    let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(FastResizeError::invalid_parameters(
+            format!("GIF frames are limited to 65535x65535, got {}x{}", width, height)
+        ));
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut buffer, width, height, &[]).map_err(gif_encoding_error)?;
+        encoder
+            .set_repeat(match animation.loop_count {
+                0 => gif::Repeat::Infinite,
+                count => gif::Repeat::Finite(count),
+            })
+            .map_err(gif_encoding_error)?;
+
+        for frame in &animation.frames {
+            let mut pixels = frame.image.as_raw().clone();
+            let mut gif_frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+            // GIF delays are in centiseconds
+            gif_frame.delay = ((frame.delay_ms + 5) / 10).min(u32::from(u16::MAX)) as u16;
+            gif_frame.dispose = match frame.disposal {
+                FrameDisposal::Keep => gif::DisposalMethod::Keep,
+                FrameDisposal::Background => gif::DisposalMethod::Background,
+                FrameDisposal::Previous => gif::DisposalMethod::Previous,
+            };
+            encoder.write_frame(&gif_frame).map_err(gif_encoding_error)?;
+        }
+    }
+
+    Ok(buffer)
+}
+
+fn gif_encoding_error(error: gif::EncodingError) -> FastResizeError {
+    encoding_error(image::ImageFormat::Gif, &error.to_string())
+}
+
+fn encode_webp_animation(animation: &Animation, config: &ResizeConfig) -> Result<Vec<u8>> {
+    use libwebp_sys as sys;
+
+    // Frees the encoder on every exit path
+    struct AnimEncoder(*mut sys::WebPAnimEncoder);
+
+    impl Drop for AnimEncoder {
+        fn drop(&mut self) {
+            unsafe { sys::WebPAnimEncoderDelete(self.0) }
+        }
+    }
+
+    impl AnimEncoder {
+        fn error(&self) -> FastResizeError {
+            let message = unsafe { sys::WebPAnimEncoderGetError(self.0) };
+            if message.is_null() {
+                return webp_error("animation encoding failed");
+            }
+            webp_error(&unsafe { std::ffi::CStr::from_ptr(message) }.to_string_lossy())
+        }
+    }
+
+    let (width, height) = animation.dimensions();
+    let webp_config = webp_config(config.quality, &config.webp)?;
+    let abi_version = sys::WEBP_MUX_ABI_VERSION as i32;
+
+    // The webp crate's AnimEncoder ends the stream at timestamp 0, which
+    // shortens the last frame, so drive libwebp directly
+    let encoder = unsafe {
+        let mut options = std::mem::MaybeUninit::<sys::WebPAnimEncoderOptions>::uninit();
+        if sys::WebPAnimEncoderOptionsInitInternal(options.as_mut_ptr(), abi_version) == 0 {
+            return Err(webp_error("failed to initialize animation options"));
+        }
+        let mut options = options.assume_init();
+        options.anim_params.loop_count = i32::from(animation.loop_count);
+        options.anim_params.bgcolor = 0;
+
+        let encoder = sys::WebPAnimEncoderNewInternal(width as i32, height as i32, &options, abi_version);
+        if encoder.is_null() {
+            return Err(webp_error("failed to create animation encoder"));
+        }
+        AnimEncoder(encoder)
+    };
+
+    // Frames are placed by start time; the final timestamp ends the last frame
+    let mut timestamp: i32 = 0;
+    for frame in &animation.frames {
+        let mut picture = sys::WebPPicture::new()
+            .map_err(|_| webp_error("failed to initialize picture"))?;
+        picture.use_argb = 1;
+        picture.width = width as i32;
+        picture.height = height as i32;
+
+        let added = unsafe {
+            let imported = sys::WebPPictureImportRGBA(&mut picture, frame.image.as_ptr(), (width * 4) as i32) != 0;
+            let added = imported && sys::WebPAnimEncoderAdd(encoder.0, &mut picture, timestamp, &webp_config) != 0;
+            sys::WebPPictureFree(&mut picture);
+            added
+        };
+        if !added {
+            return Err(encoder.error());
+        }
+
+        timestamp = timestamp.saturating_add(i32::try_from(frame.delay_ms).unwrap_or(i32::MAX));
+    }
+
+    unsafe {
+        if sys::WebPAnimEncoderAdd(encoder.0, std::ptr::null_mut(), timestamp, std::ptr::null()) == 0 {
+            return Err(encoder.error());
+        }
+
+        let mut data = sys::WebPData::default();
+        if sys::WebPAnimEncoderAssemble(encoder.0, &mut data) == 0 {
+            return Err(encoder.error());
+        }
+        let encoded = std::slice::from_raw_parts(data.bytes, data.size).to_vec();
+        sys::WebPDataClear(&mut data);
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    /// Three frames: red, green, blue squares moving across a transparent canvas
+    fn test_animation() -> Animation {
+        let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+        let frames = colors
+            .iter()
+            .enumerate()
+            .map(|(index, &color)| AnimationFrame {
+                image: RgbaImage::from_fn(60, 40, |x, _| {
+                    if x / 20 == index as u32 { Rgba(color) } else { Rgba([0, 0, 0, 0]) }
+                }),
+                delay_ms: 100 * (index as u32 + 1),
+                disposal: FrameDisposal::Background,
+            })
+            .collect();
+
+        Animation { frames, loop_count: 3 }
+    }
+
+    #[test]
+    fn test_gif_round_trip() {
+        let animation = test_animation();
+        let data = encode_animation(&animation, ImageFormat::Gif, &ResizeConfig::new()).unwrap();
+        let decoded = decode_animation(&data, ImageFormat::Gif).unwrap().unwrap();
+
+        assert_eq!(decoded.loop_count, 3);
+        assert_eq!(decoded.dimensions(), (60, 40));
+        let delays: Vec<u32> = decoded.frames.iter().map(|f| f.delay_ms).collect();
+        assert_eq!(delays, [100, 200, 300]);
+        assert!(decoded.frames.iter().all(|f| f.disposal == FrameDisposal::Background));
+
+        assert_eq!(decoded.frames[1].image.get_pixel(30, 20).0, [0, 255, 0, 255]);
+        assert_eq!(decoded.frames[1].image.get_pixel(5, 20).0[3], 0);
+    }
+
+    #[test]
+    fn test_webp_round_trip() {
+        let animation = test_animation();
+        let config = ResizeConfig::new().quality(90);
+        let data = encode_animation(&animation, ImageFormat::WebP, &config).unwrap();
+        let decoded = decode_animation(&data, ImageFormat::WebP).unwrap().unwrap();
+
+        assert_eq!(decoded.loop_count, 3);
+        assert_eq!(decoded.frames.len(), 3);
+        let delays: Vec<u32> = decoded.frames.iter().map(|f| f.delay_ms).collect();
+        assert_eq!(delays, [100, 200, 300]);
+
+        // Earlier frames do not bleed into later ones
+        let pixel = decoded.frames[2].image.get_pixel(10, 20).0;
+        assert_eq!(pixel[3], 0, "{:?}", pixel);
+        let pixel = decoded.frames[2].image.get_pixel(50, 20).0;
+        assert!(pixel[2] > 200 && pixel[0] < 50, "{:?}", pixel);
+    }
+
+    #[test]
+    fn test_stills_are_not_animations() {
+        let still = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, Rgba([1, 2, 3, 255])));
+        let gif = crate::processing::encode_image(&still, ImageFormat::Gif, &ResizeConfig::new()).unwrap();
+        assert!(decode_animation(&gif, ImageFormat::Gif).unwrap().is_none());
+
+        let webp = crate::processing::encode_image(&still, ImageFormat::WebP, &ResizeConfig::new()).unwrap();
+        assert!(decode_animation(&webp, ImageFormat::WebP).unwrap().is_none());
+
+        assert!(!is_animated_format(ImageFormat::Jpeg));
+    }
+}
@@ -0,0 +1,517 @@
+//! Built-in performance benchmark on synthetic images
+//!
+//! Images are generated deterministically in memory, so results are comparable
+//! across machines and runs without shipping a test corpus.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use image::{DynamicImage, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{FilterType, ImageFormat, ResizeConfig, ResizeMode};
+use crate::error::{Result, FastResizeError};
+use super::formats::encode_image;
+use super::resize::ImageResizer;
+
+/// Synthetic image sizes used by the benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BenchmarkSize {
+    /// 640x480 (0.3 MP)
+    Small,
+    /// 1920x1080 (2 MP)
+    Medium,
+    /// 4032x3024 (12 MP, typical phone camera)
+    Large,
+}
+
+impl BenchmarkSize {
+    /// All benchmark sizes, smallest first
+    pub fn all() -> &'static [BenchmarkSize] {
+        &[Self::Small, Self::Medium, Self::Large]
+    }
+
+    /// Image dimensions for this size
+    pub fn dimensions(self) -> (u32, u32) {
+        match self {
+            Self::Small => (640, 480),
+            Self::Medium => (1920, 1080),
+            Self::Large => (4032, 3024),
+        }
+    }
+
+    /// Lowercase size name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Small => "small",
+            Self::Medium => "medium",
+            Self::Large => "large",
+        }
+    }
+}
+
+impl FromStr for BenchmarkSize {
+    type Err = FastResizeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "small" => Ok(Self::Small),
+            "medium" => Ok(Self::Medium),
+            "large" => Ok(Self::Large),
+            other => Err(FastResizeError::invalid_parameters(
+                format!("Unknown benchmark size '{}', expected small, medium or large", other)
+            )),
+        }
+    }
+}
+
+/// Timed phase of the processing pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BenchmarkPhase {
+    Decode,
+    Resize,
+    Encode,
+}
+
+impl BenchmarkPhase {
+    /// Lowercase phase name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Decode => "decode",
+            Self::Resize => "resize",
+            Self::Encode => "encode",
+        }
+    }
+}
+
+/// Benchmark configuration
+#[derive(Debug, Clone)]
+pub struct BenchmarkConfig {
+    /// Synthetic image sizes to run
+    pub sizes: Vec<BenchmarkSize>,
+    /// Formats for the encode phase, and the decode phase where they can be decoded
+    pub formats: Vec<ImageFormat>,
+    /// Filters for the resize phase
+    pub filters: Vec<FilterType>,
+    /// Timed iterations per measurement
+    pub iterations: u32,
+    /// Encoder quality
+    pub quality: u8,
+    /// Scale factor used for the resize phase
+    pub scale: f32,
+}
+
+impl BenchmarkConfig {
+    /// Create a configuration covering every format and filter at medium size
+    pub fn new() -> Self {
+        Self {
+            sizes: vec![BenchmarkSize::Medium],
+            formats: ImageFormat::all().to_vec(),
+            filters: FilterType::all().to_vec(),
+            iterations: 10,
+            quality: 85,
+            scale: 0.5,
+        }
+    }
+
+    /// Set the image sizes
+    pub fn sizes(mut self, sizes: Vec<BenchmarkSize>) -> Self {
+        self.sizes = sizes;
+        self
+    }
+
+    /// Set the formats
+    pub fn formats(mut self, formats: Vec<ImageFormat>) -> Self {
+        self.formats = formats;
+        self
+    }
+
+    /// Set the filters
+    pub fn filters(mut self, filters: Vec<FilterType>) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    /// Set the number of timed iterations
+    pub fn iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Set the encoder quality
+    pub fn quality(mut self, quality: u8) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Validate the configuration
+    pub fn validate(&self) -> Result<()> {
+        if self.iterations == 0 {
+            return Err(FastResizeError::invalid_parameters("Iterations must be greater than 0"));
+        }
+        if self.sizes.is_empty() {
+            return Err(FastResizeError::invalid_parameters("At least one benchmark size is required"));
+        }
+        if self.quality == 0 || self.quality > 100 {
+            return Err(FastResizeError::invalid_parameters(
+                format!("Quality must be between 1-100, got {}", self.quality)
+            ));
+        }
+        if self.scale <= 0.0 || self.scale > 10.0 {
+            return Err(FastResizeError::invalid_parameters(
+                "Scale factor must be between 0.1 and 10.0"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Timing statistics for one measurement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub size: BenchmarkSize,
+    pub width: u32,
+    pub height: u32,
+    pub phase: BenchmarkPhase,
+    /// Format name for decode/encode, filter name for resize
+    pub variant: String,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub iterations: u32,
+}
+
+impl BenchmarkResult {
+    /// Stable key used to match results against a baseline
+    pub fn key(&self) -> String {
+        format!("{}/{}/{}", self.size.name(), self.phase.name(), self.variant)
+    }
+}
+
+/// Complete benchmark run, serializable as a baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// FastResize version that produced the report
+    pub version: String,
+    pub results: Vec<BenchmarkResult>,
+}
+
+/// Median comparison of one measurement against a baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkComparison {
+    pub key: String,
+    pub baseline_median_ms: f64,
+    pub current_median_ms: f64,
+    /// Positive values are slower than the baseline
+    pub change_percent: f64,
+    pub regressed: bool,
+}
+
+impl BenchmarkReport {
+    /// Load a report from a JSON file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| FastResizeError::config(
+                format!("Failed to read benchmark baseline {:?}: {}", path.as_ref(), e)
+            ))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| FastResizeError::SerdeError(format!("JSON parse error: {}", e)))
+    }
+
+    /// Save the report as pretty-printed JSON
+    pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| FastResizeError::SerdeError(format!("JSON serialize error: {}", e)))?;
+
+        std::fs::write(&path, content)
+            .map_err(|e| FastResizeError::config(
+                format!("Failed to write benchmark results {:?}: {}", path.as_ref(), e)
+            ))
+    }
+
+    /// Compare medians against a baseline
+    ///
+    /// Measurements missing from the baseline are skipped. A measurement regresses
+    /// when its median is more than `threshold_percent` slower than the baseline.
+    pub fn compare(&self, baseline: &BenchmarkReport, threshold_percent: f64) -> Vec<BenchmarkComparison> {
+        self.results
+            .iter()
+            .filter_map(|current| {
+                let key = current.key();
+                let previous = baseline.results.iter().find(|r| r.key() == key)?;
+                let change_percent = if previous.median_ms > 0.0 {
+                    (current.median_ms - previous.median_ms) / previous.median_ms * 100.0
+                } else {
+                    0.0
+                };
+
+                Some(BenchmarkComparison {
+                    key,
+                    baseline_median_ms: previous.median_ms,
+                    current_median_ms: current.median_ms,
+                    change_percent,
+                    regressed: change_percent > threshold_percent,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Runs the decode, resize and encode benchmarks
+pub struct Benchmark {
+    config: BenchmarkConfig,
+}
+
+impl Benchmark {
+    /// Create a benchmark with the given configuration
+    pub fn new(config: BenchmarkConfig) -> Self {
+        Self { config }
+    }
+
+    /// Run every measurement, calling `on_result` as each one finishes
+    pub fn run<F: FnMut(&BenchmarkResult)>(&self, mut on_result: F) -> Result<BenchmarkReport> {
+        self.config.validate()?;
+
+        let mut results = Vec::new();
+        let mode = ResizeMode::Scale { factor: self.config.scale };
+        let encoder_config = ResizeConfig::new().quality(self.config.quality);
+
+        for &size in &self.config.sizes {
+            let (width, height) = size.dimensions();
+            let source = synthetic_image(width, height, u64::from(width) << 32 | u64::from(height));
+
+            let mut record = |phase: BenchmarkPhase, variant: &str, samples: Vec<Duration>| {
+                let result = self.summarize(size, phase, variant, samples);
+                on_result(&result);
+                results.push(result);
+            };
+
+            // Formats without a decoder only get the encode phase
+            for &format in self.config.formats.iter().filter(|f| f.can_decode()) {
+                let encoded = encode_image(&source, format, &encoder_config)?;
+                let samples = self.measure(|| {
+                    image::load_from_memory(&encoded).map(|_| ()).map_err(Into::into)
+                })?;
+                record(BenchmarkPhase::Decode, format.extension(), samples);
+            }
+
+            for &filter in &self.config.filters {
+                let resizer = ImageResizer::with_filter(filter);
+                let samples = self.measure(|| resizer.resize(&source, &mode).map(|_| ()))?;
+                record(BenchmarkPhase::Resize, filter.name(), samples);
+            }
+
+            let resized = ImageResizer::new().resize(&source, &mode)?;
+            for &format in &self.config.formats {
+                let samples = self.measure(|| {
+                    encode_image(&resized, format, &encoder_config).map(|_| ())
+                })?;
+                record(BenchmarkPhase::Encode, format.extension(), samples);
+            }
+        }
+
+        Ok(BenchmarkReport {
+            version: crate::VERSION.to_string(),
+            results,
+        })
+    }
+
+    /// Time an operation after one untimed warm-up run
+    fn measure<F: FnMut() -> Result<()>>(&self, mut operation: F) -> Result<Vec<Duration>> {
+        operation()?;
+
+        (0..self.config.iterations)
+            .map(|_| {
+                let start = Instant::now();
+                std::hint::black_box(operation()?);
+                Ok(start.elapsed())
+            })
+            .collect()
+    }
+
+    fn summarize(
+        &self,
+        size: BenchmarkSize,
+        phase: BenchmarkPhase,
+        variant: &str,
+        samples: Vec<Duration>,
+    ) -> BenchmarkResult {
+        let (width, height) = size.dimensions();
+        let mut millis: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(f64::total_cmp);
+
+        BenchmarkResult {
+            size,
+            width,
+            height,
+            phase,
+            variant: variant.to_string(),
+            median_ms: median(&millis),
+            p95_ms: percentile(&millis, 95.0),
+            iterations: self.config.iterations,
+        }
+    }
+}
+
+/// Generate a deterministic synthetic photo-like image
+///
+/// Combines smooth gradients, hard-edged shapes and seeded noise so that
+/// encoders and filters see both flat regions and high-frequency detail.
+pub fn synthetic_image(width: u32, height: u32, seed: u64) -> DynamicImage {
+    let mut state = seed | 1;
+    let mut next_noise = move || {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % 32) as i32 - 16
+    };
+
+    let w = width.max(1) as f32;
+    let h = height.max(1) as f32;
+
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        let fx = x as f32 / w;
+        let fy = y as f32 / h;
+
+        let mut r = 255.0 * fx;
+        let mut g = 255.0 * fy;
+        let mut b = 128.0 + 127.0 * ((fx * 12.0).sin() * (fy * 8.0).cos());
+
+        // Checkerboard block in the upper-left quadrant
+        if fx < 0.5 && fy < 0.5 && ((x / 16) + (y / 16)) % 2 == 0 {
+            r = 255.0 - r;
+            g = 255.0 - g;
+        }
+
+        // Solid disc in the lower-right quadrant
+        let (dx, dy) = (fx - 0.7, fy - 0.7);
+        if dx * dx + dy * dy < 0.02 {
+            r = 240.0;
+            g = 200.0;
+            b = 40.0;
+        }
+
+        let noise = next_noise();
+        let channel = |value: f32| (value as i32 + noise).clamp(0, 255) as u8;
+        Rgb([channel(r), channel(g), channel(b)])
+    });
+
+    DynamicImage::ImageRgb8(image)
+}
+
+/// Median of sorted samples
+fn median(sorted: &[f64]) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+        n => sorted[n / 2],
+    }
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthetic_image_is_deterministic() {
+        let a = synthetic_image(64, 48, 42);
+        let b = synthetic_image(64, 48, 42);
+        let c = synthetic_image(64, 48, 7);
+
+        assert_eq!((a.width(), a.height()), (64, 48));
+        assert_eq!(a.as_bytes(), b.as_bytes());
+        assert_ne!(a.as_bytes(), c.as_bytes());
+    }
+
+    #[test]
+    fn test_statistics() {
+        let samples = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+        assert_eq!(median(&samples), 5.5);
+        assert_eq!(percentile(&samples, 95.0), 10.0);
+        assert_eq!(median(&samples[..3]), 2.0);
+        assert_eq!(percentile(&samples[..1], 95.0), 1.0);
+    }
+
+    #[test]
+    fn test_size_parsing() {
+        assert_eq!("small".parse::<BenchmarkSize>().unwrap(), BenchmarkSize::Small);
+        assert_eq!(" Large ".parse::<BenchmarkSize>().unwrap(), BenchmarkSize::Large);
+        assert!("huge".parse::<BenchmarkSize>().is_err());
+    }
+
+    #[test]
+    fn test_run_and_compare_with_baseline() {
+        let config = BenchmarkConfig::new()
+            .sizes(vec![BenchmarkSize::Small])
+            .formats(vec![ImageFormat::Jpeg, ImageFormat::Png])
+            .filters(vec![FilterType::Nearest, FilterType::Triangle])
+            .iterations(2);
+
+        let mut seen = 0;
+        let report = Benchmark::new(config).run(|_| seen += 1).unwrap();
+
+        // Decode and encode per format, resize per filter
+        assert_eq!(report.results.len(), 6);
+        assert_eq!(seen, 6);
+        assert!(report.results.iter().all(|r| r.p95_ms >= r.median_ms));
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("baseline.json");
+        report.to_file(&path).unwrap();
+
+        let mut baseline = BenchmarkReport::from_file(&path).unwrap();
+        assert_eq!(baseline.results.len(), 6);
+        for result in &mut baseline.results {
+            result.median_ms = report.results[0].median_ms.max(1.0) * 1000.0;
+        }
+        baseline.results[0].median_ms = report.results[0].median_ms / 2.0;
+        baseline.results.pop();
+
+        let comparisons = report.compare(&baseline, 10.0);
+        assert_eq!(comparisons.len(), 5);
+        assert!(comparisons[0].regressed);
+        assert!(comparisons[1..].iter().all(|c| !c.regressed));
+    }
+
+    #[test]
+    fn test_run_default_formats_and_filters() {
+        let config = BenchmarkConfig::new()
+            .sizes(vec![BenchmarkSize::Small])
+            .iterations(1);
+        let report = Benchmark::new(config).run(|_| {}).unwrap();
+
+        let count = |phase| report.results.iter().filter(|r| r.phase == phase).count();
+        let decodable = ImageFormat::all().iter().filter(|f| f.can_decode()).count();
+        assert_eq!(count(BenchmarkPhase::Decode), decodable);
+        assert_eq!(count(BenchmarkPhase::Resize), FilterType::all().len());
+        assert_eq!(count(BenchmarkPhase::Encode), ImageFormat::all().len());
+        assert!(!report.results.iter().any(|r| r.key() == "small/decode/avif"));
+        assert!(report.results.iter().any(|r| r.key() == "small/encode/avif"));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(BenchmarkConfig::new().iterations(0).validate().is_err());
+        assert!(BenchmarkConfig::new().sizes(Vec::new()).validate().is_err());
+        assert!(BenchmarkConfig::new().quality(0).validate().is_err());
+    }
+}
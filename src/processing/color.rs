@@ -0,0 +1,247 @@
+//! ICC color profile conversion
+
+use image::{DynamicImage, ImageBuffer, Pixel};
+use moxcms::{CmsError, ColorProfile, DataColorSpace, Layout, TransformOptions};
+use tracing::debug;
+
+use crate::config::ColorSpace;
+
+/// Plan for bringing resized pixels into the output color space
+///
+/// Built once per input from its embedded ICC profile, then applied to the
+/// resized image (or every frame of an animation).
+pub struct ColorConversion {
+    /// Source and target profiles when pixels must be converted
+    transform: Option<(ColorProfile, ColorProfile)>,
+    /// Profile embedded in color outputs
+    profile: Option<Vec<u8>>,
+    /// Profile embedded in grayscale outputs
+    gray_profile: Option<Vec<u8>>,
+}
+
+impl ColorConversion {
+    /// Plan the conversion of an input with an optional embedded ICC profile
+    ///
+    /// Untagged inputs count as sRGB. `embeds_profile` says whether the output
+    /// format can carry an ICC profile; outputs that cannot are converted to
+    /// sRGB so they display correctly without one.
+    pub fn new(source_icc: Option<&[u8]>, color_space: ColorSpace, embeds_profile: bool) -> Self {
+        let source = source_icc.and_then(|icc| match ColorProfile::new_from_slice(icc) {
+            Ok(profile) => Some(profile),
+            Err(e) => {
+                debug!("Ignoring unreadable ICC profile: {}", e);
+                None
+            }
+        });
+        let gray_profile = source.as_ref()
+            .filter(|profile| embeds_profile && profile.color_space == DataColorSpace::Gray)
+            .and(source_icc.map(<[u8]>::to_vec));
+        let source = source.filter(|profile| profile.color_space == DataColorSpace::Rgb);
+
+        // Keep the pixels as they are, tagged with their own profile
+        let keep = |source: Option<ColorProfile>| Self {
+            transform: None,
+            profile: source.and(source_icc).filter(|_| embeds_profile).map(<[u8]>::to_vec),
+            gray_profile: gray_profile.clone(),
+        };
+
+        let target = match color_space {
+            _ if !embeds_profile => source.is_some().then(ColorProfile::new_srgb),
+            ColorSpace::Srgb => Some(ColorProfile::new_srgb()),
+            ColorSpace::AdobeRgb => Some(ColorProfile::new_adobe_rgb()),
+            ColorSpace::DisplayP3 => Some(ColorProfile::new_display_p3()),
+            ColorSpace::Auto => None,
+        };
+        let Some(target) = target else {
+            return keep(source);
+        };
+        let profile = if embeds_profile { encode(&target) } else { None };
+
+        let source = match source {
+            Some(source) => source,
+            // Untagged pixels are already sRGB
+            None if color_space == ColorSpace::Srgb => {
+                return Self { transform: None, profile, gray_profile };
+            }
+            None => ColorProfile::new_srgb(),
+        };
+
+        if let Err(e) = source.create_transform_8bit(Layout::Rgb, &target, Layout::Rgb, TransformOptions::default()) {
+            // The embedded profile still describes the unconverted pixels
+            debug!("Cannot convert from the embedded ICC profile: {}", e);
+            return keep(Some(source));
+        }
+
+        Self { transform: Some((source, target)), profile, gray_profile }
+    }
+
+    /// Whether pixels change
+    pub fn converts(&self) -> bool {
+        self.transform.is_some()
+    }
+
+    /// Convert color pixels into the output color space
+    ///
+    /// Grayscale images are returned unchanged.
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        let Some((source, target)) = &self.transform else {
+            return image;
+        };
+        if !image.color().has_color() {
+            return image;
+        }
+
+        match convert(image.clone(), source, target) {
+            Ok(converted) => converted,
+            Err(e) => {
+                debug!("Color conversion failed, keeping original pixels: {}", e);
+                image
+            }
+        }
+    }
+
+    /// ICC profile to embed with an output image
+    pub fn profile_for(&self, image: &DynamicImage) -> Option<Vec<u8>> {
+        if image.color().has_color() {
+            self.profile.clone()
+        } else {
+            self.gray_profile.clone()
+        }
+    }
+}
+
+fn encode(profile: &ColorProfile) -> Option<Vec<u8>> {
+    match profile.encode() {
+        Ok(icc) => Some(icc),
+        Err(e) => {
+            debug!("Failed to encode output ICC profile: {}", e);
+            None
+        }
+    }
+}
+
+fn convert(image: DynamicImage, source: &ColorProfile, target: &ColorProfile) -> Result<DynamicImage, CmsError> {
+    let options = TransformOptions::default();
+
+    Ok(match image {
+        DynamicImage::ImageRgb8(pixels) => {
+            let transform = source.create_transform_8bit(Layout::Rgb, target, Layout::Rgb, options)?;
+            DynamicImage::ImageRgb8(transform_buffer(pixels, |src, dst| transform.transform(src, dst))?)
+        }
+        DynamicImage::ImageRgba8(pixels) => {
+            let transform = source.create_transform_8bit(Layout::Rgba, target, Layout::Rgba, options)?;
+            DynamicImage::ImageRgba8(transform_buffer(pixels, |src, dst| transform.transform(src, dst))?)
+        }
+        DynamicImage::ImageRgb16(pixels) => {
+            let transform = source.create_transform_16bit(Layout::Rgb, target, Layout::Rgb, options)?;
+            DynamicImage::ImageRgb16(transform_buffer(pixels, |src, dst| transform.transform(src, dst))?)
+        }
+        DynamicImage::ImageRgba16(pixels) => {
+            let transform = source.create_transform_16bit(Layout::Rgba, target, Layout::Rgba, options)?;
+            DynamicImage::ImageRgba16(transform_buffer(pixels, |src, dst| transform.transform(src, dst))?)
+        }
+        // Floating point images go through 16 bits
+        other => convert(DynamicImage::ImageRgba16(other.to_rgba16()), source, target)?,
+    })
+}
+
+fn transform_buffer<P, F>(
+    pixels: ImageBuffer<P, Vec<P::Subpixel>>,
+    transform: F,
+) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, CmsError>
+where
+    P: Pixel,
+    F: Fn(&[P::Subpixel], &mut [P::Subpixel]) -> Result<(), CmsError>,
+{
+    let (width, height) = pixels.dimensions();
+    let source = pixels.into_raw();
+    let mut converted = source.clone();
+    transform(&source, &mut converted)?;
+    Ok(ImageBuffer::from_raw(width, height, converted).expect("buffer size is unchanged"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn adobe_rgb_icc() -> Vec<u8> {
+        ColorProfile::new_adobe_rgb().encode().unwrap()
+    }
+
+    #[test]
+    fn test_adobe_rgb_converts_to_srgb() {
+        // Pure Adobe RGB green lies outside sRGB, so it clips; a mid tone
+        // gets more saturated once it is read in the wider space
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([90, 160, 90])));
+        let icc = adobe_rgb_icc();
+
+        let conversion = ColorConversion::new(Some(&icc), ColorSpace::Srgb, true);
+        assert!(conversion.converts());
+        let converted = conversion.apply(image.clone()).to_rgb8();
+        let [r, g, b] = converted.get_pixel(0, 0).0;
+        assert!(g > 160 && r < 90 && b < 90, "converted to {:?}", [r, g, b]);
+
+        let srgb = ColorProfile::new_from_slice(&conversion.profile_for(&image).unwrap()).unwrap();
+        assert_eq!(srgb.color_space, DataColorSpace::Rgb);
+    }
+
+    #[test]
+    fn test_auto_keeps_embedded_profile() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([90, 160, 90])));
+        let icc = adobe_rgb_icc();
+
+        let conversion = ColorConversion::new(Some(&icc), ColorSpace::Auto, true);
+        assert!(!conversion.converts());
+        assert_eq!(conversion.apply(image.clone()).to_rgb8(), image.to_rgb8());
+        assert_eq!(conversion.profile_for(&image), Some(icc.clone()));
+
+        // Formats without a profile slot get sRGB pixels instead
+        let conversion = ColorConversion::new(Some(&icc), ColorSpace::Auto, false);
+        assert!(conversion.converts());
+        assert_eq!(conversion.profile_for(&image), None);
+
+        // Untagged input needs no conversion to sRGB, only a tag
+        let conversion = ColorConversion::new(None, ColorSpace::Srgb, true);
+        assert!(!conversion.converts());
+        assert!(conversion.profile_for(&image).is_some());
+        let gray = DynamicImage::ImageLuma8(image.to_luma8());
+        assert_eq!(conversion.profile_for(&gray), None);
+
+        let conversion = ColorConversion::new(Some(b"not a profile"), ColorSpace::Auto, true);
+        assert_eq!(conversion.profile_for(&image), None);
+    }
+
+    #[tokio::test]
+    async fn test_engine_converts_tagged_input() {
+        use crate::config::ResizeConfig;
+        use crate::processing::{encode_image, Metadata, ProcessingEngine};
+        use crate::config::ImageFormat;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("adobe.png");
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(40, 30, Rgb([90, 160, 90])));
+        let encoded = encode_image(&image, ImageFormat::Png, &ResizeConfig::new()).unwrap();
+        let metadata = Metadata { icc: Some(adobe_rgb_icc()), ..Metadata::default() };
+        std::fs::write(&input_path, metadata.embed(encoded, ImageFormat::Png).unwrap()).unwrap();
+
+        let engine = ProcessingEngine::new();
+        let output_path = temp_dir.path().join("srgb.png");
+        let config = ResizeConfig::new().width(20).color_space(ColorSpace::Srgb);
+        engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        let output = std::fs::read(&output_path).unwrap();
+        let icc = Metadata::read(&output, ImageFormat::Png).icc.unwrap();
+        assert_ne!(icc, adobe_rgb_icc());
+        let [r, g, b] = image::load_from_memory(&output).unwrap().to_rgb8().get_pixel(0, 0).0;
+        assert!(g > 160 && r < 90 && b < 90, "converted to {:?}", [r, g, b]);
+
+        // Auto carries the original profile and pixels, even when stripping metadata
+        let output_path = temp_dir.path().join("kept.png");
+        let config = ResizeConfig::new().width(20).color_space(ColorSpace::Auto);
+        engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        let output = std::fs::read(&output_path).unwrap();
+        assert_eq!(Metadata::read(&output, ImageFormat::Png).icc, Some(adobe_rgb_icc()));
+        let pixel = image::load_from_memory(&output).unwrap().to_rgb8().get_pixel(0, 0).0;
+        assert_eq!(pixel, [90, 160, 90]);
+    }
+}
@@ -0,0 +1,235 @@
+//! Crop position selection for fill resizing
+
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, GrayImage};
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::error::{Result, FastResizeError};
+
+/// Longest side of the working image used for attention detection
+const ATTENTION_SIZE: u32 = 160;
+
+/// Find the focus point of the most detailed region for a fill crop
+///
+/// Scores every crop window with the target aspect ratio by its edge energy
+/// (Sobel gradient magnitude) on a downscaled copy and returns the center of
+/// the best window as fractions of width and height (0.0-1.0). Ties go to
+/// the window closest to the image center.
+pub fn attention_focus(image: &DynamicImage, target_width: u32, target_height: u32) -> (f32, f32) {
+    if image.width() == 0 || image.height() == 0 || target_width == 0 || target_height == 0 {
+        return (0.5, 0.5);
+    }
+
+    let luma = image.thumbnail(ATTENTION_SIZE, ATTENTION_SIZE).to_luma8();
+    let (width, height) = luma.dimensions();
+    if width < 3 || height < 3 {
+        return (0.5, 0.5);
+    }
+
+    // Crop window in working-image pixels; only one axis is cropped
+    let image_aspect = image.width() as f32 / image.height() as f32;
+    let target_aspect = target_width as f32 / target_height as f32;
+    let (window_width, window_height) = if image_aspect > target_aspect {
+        let window = (height as f32 * target_aspect).round() as u32;
+        (window.clamp(1, width), height)
+    } else {
+        let window = (width as f32 / target_aspect).round() as u32;
+        (width, window.clamp(1, height))
+    };
+
+    let integral = SummedArea::new(&edge_energy(&luma), width, height);
+
+    let center_x = (width - window_width) as f32 / 2.0;
+    let center_y = (height - window_height) as f32 / 2.0;
+    let mut best = (0, 0);
+    let mut best_score = f64::MIN;
+    let mut best_distance = f32::MAX;
+
+    for y in 0..=height - window_height {
+        for x in 0..=width - window_width {
+            let score = integral.sum(x, y, window_width, window_height);
+            let distance = (x as f32 - center_x).abs() + (y as f32 - center_y).abs();
+            if score > best_score || (score == best_score && distance < best_distance) {
+                best = (x, y);
+                best_score = score;
+                best_distance = distance;
+            }
+        }
+    }
+
+    (
+        (best.0 as f32 + window_width as f32 / 2.0) / width as f32,
+        (best.1 as f32 + window_height as f32 / 2.0) / height as f32,
+    )
+}
+
+/// Sobel gradient magnitude per pixel (zero on the border)
+fn edge_energy(luma: &GrayImage) -> Vec<f32> {
+    let (width, height) = luma.dimensions();
+    let mut energy = vec![0.0; (width * height) as usize];
+    let pixel = |x: u32, y: u32| f32::from(luma.get_pixel(x, y).0[0]);
+
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let gx = pixel(x + 1, y - 1) + 2.0 * pixel(x + 1, y) + pixel(x + 1, y + 1)
+                - pixel(x - 1, y - 1) - 2.0 * pixel(x - 1, y) - pixel(x - 1, y + 1);
+            let gy = pixel(x - 1, y + 1) + 2.0 * pixel(x, y + 1) + pixel(x + 1, y + 1)
+                - pixel(x - 1, y - 1) - 2.0 * pixel(x, y - 1) - pixel(x + 1, y - 1);
+            energy[(y * width + x) as usize] = gx.abs() + gy.abs();
+        }
+    }
+
+    energy
+}
+
+/// Summed-area table for constant-time window sums
+struct SummedArea {
+    sums: Vec<f64>,
+    stride: usize,
+}
+
+impl SummedArea {
+    fn new(values: &[f32], width: u32, height: u32) -> Self {
+        let stride = width as usize + 1;
+        let mut sums = vec![0.0; stride * (height as usize + 1)];
+
+        for y in 0..height as usize {
+            let mut row = 0.0;
+            for x in 0..width as usize {
+                row += f64::from(values[y * width as usize + x]);
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row;
+            }
+        }
+
+        Self { sums, stride }
+    }
+
+    fn sum(&self, x: u32, y: u32, width: u32, height: u32) -> f64 {
+        let (x0, y0) = (x as usize, y as usize);
+        let (x1, y1) = (x0 + width as usize, y0 + height as usize);
+        self.sums[y1 * self.stride + x1] - self.sums[y0 * self.stride + x1]
+            - self.sums[y1 * self.stride + x0] + self.sums[y0 * self.stride + x0]
+    }
+}
+
+/// Focus point sidecar contents, e.g. `{"focus": {"x": 0.3, "y": 0.6}}`
+#[derive(Debug, Deserialize)]
+struct FocusSidecar {
+    focus: SidecarPoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct SidecarPoint {
+    x: f32,
+    y: f32,
+}
+
+/// Candidate sidecar paths for an image: `photo.jpg.json`, then `photo.json`
+pub fn sidecar_paths(image_path: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(file_name) = image_path.file_name() {
+        let mut name = file_name.to_os_string();
+        name.push(".json");
+        paths.push(image_path.with_file_name(name));
+    }
+    paths.push(image_path.with_extension("json"));
+    paths
+}
+
+/// Read the focus point for an image from its JSON sidecar
+///
+/// Returns `None` when no sidecar exists. Coordinates are fractions of
+/// width and height (0.0-1.0).
+pub async fn read_sidecar_focus(image_path: &Path) -> Result<Option<(f32, f32)>> {
+    let mut found = None;
+    for path in sidecar_paths(image_path) {
+        if fs::metadata(&path).await.is_ok_and(|metadata| metadata.is_file()) {
+            found = Some(path);
+            break;
+        }
+    }
+    let Some(path) = found else {
+        return Ok(None);
+    };
+
+    let content = fs::read_to_string(&path).await?;
+    let sidecar: FocusSidecar = serde_json::from_str(&content)
+        .map_err(|e| FastResizeError::validation(
+            format!("Invalid focus sidecar {}: {}", path.display(), e),
+            Some(image_path.to_path_buf()),
+        ))?;
+
+    let SidecarPoint { x, y } = sidecar.focus;
+    if !(0.0..=1.0).contains(&x) || !(0.0..=1.0).contains(&y) {
+        return Err(FastResizeError::validation(
+            format!("Focus point in {} must be between 0.0-1.0, got ({}, {})", path.display(), x, y),
+            Some(image_path.to_path_buf()),
+        ));
+    }
+
+    Ok(Some((x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    /// Flat gray image with a checkerboard patch centered at (`patch_x`, `patch_y`)
+    fn image_with_detail(width: u32, height: u32, patch_x: u32, patch_y: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let in_patch = x.abs_diff(patch_x) < 40 && y.abs_diff(patch_y) < 40;
+            if in_patch && (x / 5 + y / 5) % 2 == 0 {
+                Rgb([250, 250, 250])
+            } else if in_patch {
+                Rgb([10, 10, 10])
+            } else {
+                Rgb([128, 128, 128])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_attention_finds_detail() {
+        let wide = image_with_detail(800, 200, 650, 100);
+        let (x, y) = attention_focus(&wide, 200, 200);
+        assert!(x > 0.7 && x < 0.9, "focus x = {}", x);
+        assert!((y - 0.5).abs() < f32::EPSILON);
+
+        let tall = image_with_detail(200, 800, 100, 120);
+        let (x, y) = attention_focus(&tall, 200, 200);
+        assert!((x - 0.5).abs() < f32::EPSILON);
+        assert!(y > 0.05 && y < 0.25, "focus y = {}", y);
+    }
+
+    #[test]
+    fn test_attention_prefers_center_on_flat_image() {
+        let flat = DynamicImage::ImageRgb8(RgbImage::from_pixel(600, 200, Rgb([90, 90, 90])));
+        let (x, y) = attention_focus(&flat, 100, 100);
+        assert!((x - 0.5).abs() < 0.01);
+        assert!((y - 0.5).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_read_sidecar_focus() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let image_path = temp_dir.path().join("photo.jpg");
+
+        assert_eq!(read_sidecar_focus(&image_path).await.unwrap(), None);
+
+        std::fs::write(temp_dir.path().join("photo.json"), r#"{"focus": {"x": 0.25, "y": 0.75}}"#).unwrap();
+        assert_eq!(read_sidecar_focus(&image_path).await.unwrap(), Some((0.25, 0.75)));
+
+        // The full-name sidecar takes precedence
+        std::fs::write(temp_dir.path().join("photo.jpg.json"), r#"{"focus": {"x": 0.1, "y": 0.2}}"#).unwrap();
+        assert_eq!(read_sidecar_focus(&image_path).await.unwrap(), Some((0.1, 0.2)));
+
+        std::fs::write(temp_dir.path().join("photo.jpg.json"), r#"{"focus": {"x": 1.5, "y": 0.2}}"#).unwrap();
+        assert!(read_sidecar_focus(&image_path).await.is_err());
+
+        std::fs::write(temp_dir.path().join("photo.jpg.json"), "not json").unwrap();
+        assert!(read_sidecar_focus(&image_path).await.is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Image format detection and handling
 
 use std::path::Path;
-use crate::config::ImageFormat;
+use crate::config::{AvifOptions, ChromaSubsampling, ColorSpace, ImageFormat, ResizeConfig, WebPOptions};
 use crate::error::{Result, FastResizeError};
 
 /// Detect image format from file extension
@@ -22,6 +22,7 @@ pub fn detect_format_from_path<P: AsRef<Path>>(path: P) -> Result<ImageFormat> {
         "gif" => Ok(ImageFormat::Gif),
         "tiff" | "tif" => Ok(ImageFormat::Tiff),
         "bmp" => Ok(ImageFormat::Bmp),
+        "avif" => Ok(ImageFormat::Avif),
         _ => Err(FastResizeError::unsupported_format(
             extension.to_string(),
             Some(path.to_path_buf())
@@ -63,6 +64,15 @@ pub fn detect_format_from_header(data: &[u8]) -> Result<ImageFormat> {
         return Ok(ImageFormat::Tiff);
     }
 
+    // AVIF: ISO BMFF ftyp box with an AVIF major or compatible brand
+    if &data[4..8] == b"ftyp" {
+        let box_size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        let brands = &data[8..box_size.clamp(12, data.len())];
+        if brands.chunks_exact(4).any(|brand| brand == b"avif" || brand == b"avis") {
+            return Ok(ImageFormat::Avif);
+        }
+    }
+
     // BMP: BM
     if data.starts_with(b"BM") {
         return Ok(ImageFormat::Bmp);
@@ -84,8 +94,241 @@ impl From<ImageFormat> for image::ImageFormat {
             ImageFormat::Gif => image::ImageFormat::Gif,
             ImageFormat::Tiff => image::ImageFormat::Tiff,
             ImageFormat::Bmp => image::ImageFormat::Bmp,
+            ImageFormat::Avif => image::ImageFormat::Avif,
+        }
+    }
+}
+
+/// Encode an image in memory using the given output format and the
+/// quality and encoder options from `config`
+pub fn encode_image(
+    image: &image::DynamicImage,
+    format: ImageFormat,
+    config: &ResizeConfig,
+) -> Result<Vec<u8>> {
+    use std::io::Cursor;
+
+    let quality = config.quality;
+    let mut buffer = Cursor::new(Vec::new());
+
+    match format {
+        ImageFormat::Jpeg => {
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality);
+            image.write_with_encoder(encoder)?;
+        }
+        // PNG uses compression level instead of quality; the default quality
+        // keeps the standard encoder settings for best speed
+        ImageFormat::Png if quality != 90 => {
+            use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+
+            // Lower quality = faster compression, larger files
+            // Higher quality = slower compression, smaller files
+            let (compression, filter) = if quality < 50 {
+                (CompressionType::Fast, FilterType::NoFilter)
+            } else if quality < 80 {
+                (CompressionType::Fast, FilterType::Sub)
+            } else if quality < 95 {
+                (CompressionType::Default, FilterType::Sub)
+            } else {
+                (CompressionType::Best, FilterType::Adaptive)
+            };
+
+            let encoder = PngEncoder::new_with_quality(&mut buffer, compression, filter);
+            image.write_with_encoder(encoder)?;
+        }
+        ImageFormat::WebP => {
+            return encode_webp(image, quality, &config.webp);
+        }
+        ImageFormat::Avif => {
+            return encode_avif(image, quality, &config.avif);
+        }
+        _ => {
+            image.write_to(&mut buffer, image::ImageFormat::from(format))?;
+        }
+    }
+
+    Ok(buffer.into_inner())
+}
+
+/// Encode WebP with libwebp, keeping the alpha channel when present
+fn encode_webp(image: &image::DynamicImage, quality: u8, options: &WebPOptions) -> Result<Vec<u8>> {
+    let config = webp_config(quality, options)?;
+
+    let encoded = if image.color().has_alpha() {
+        let rgba = image.to_rgba8();
+        webp::Encoder::from_rgba(rgba.as_raw(), rgba.width(), rgba.height())
+            .encode_advanced(&config)
+            .map(|memory| memory.to_vec())
+    } else {
+        let rgb = image.to_rgb8();
+        webp::Encoder::from_rgb(rgb.as_raw(), rgb.width(), rgb.height())
+            .encode_advanced(&config)
+            .map(|memory| memory.to_vec())
+    };
+
+    encoded.map_err(|e| webp_error(&format!("{:?}", e)))
+}
+
+/// libwebp settings for the given quality and options
+pub(crate) fn webp_config(quality: u8, options: &WebPOptions) -> Result<webp::WebPConfig> {
+    let mut config = webp::WebPConfig::new()
+        .map_err(|_| webp_error("failed to initialize encoder configuration"))?;
+    config.lossless = i32::from(options.lossless);
+    // For lossless output quality trades encoding speed for size
+    config.quality = f32::from(quality.min(100));
+    config.method = i32::from(options.method.min(6));
+    config.alpha_compression = i32::from(!options.lossless);
+    if options.lossless {
+        config.near_lossless = i32::from(options.near_lossless.unwrap_or(100).min(100));
+    }
+    Ok(config)
+}
+
+pub(crate) fn webp_error(message: &str) -> FastResizeError {
+    encoding_error(image::ImageFormat::WebP, message)
+}
+
+/// Encode AVIF with rav1e: one still AV1 frame for the full-range BT.601
+/// color planes, plus a monochrome frame for the alpha channel when present
+fn encode_avif(image: &image::DynamicImage, quality: u8, options: &AvifOptions) -> Result<Vec<u8>> {
+    use rav1e::prelude::{ChromaSampling, ColorDescription, ColorPrimaries, MatrixCoefficients, TransferCharacteristics};
+
+    let rgba = image.to_rgba8();
+    let (width, height) = (rgba.width() as usize, rgba.height() as usize);
+    let subsampled = options.subsampling == ChromaSubsampling::Yuv420;
+
+    let mut luma = Vec::with_capacity(width * height);
+    let mut blue_difference = Vec::with_capacity(width * height);
+    let mut red_difference = Vec::with_capacity(width * height);
+    for pixel in rgba.pixels() {
+        let [r, g, b, _] = pixel.0.map(f32::from);
+        let y = 0.299 * r + 0.587 * g + 0.114 * b;
+        luma.push(y.round() as u8);
+        blue_difference.push((b - y) / 1.772 + 128.0);
+        red_difference.push((r - y) / 1.402 + 128.0);
+    }
+
+    let chroma_width = if subsampled { width.div_ceil(2) } else { width };
+    let blue_difference = chroma_plane(&blue_difference, width, height, subsampled);
+    let red_difference = chroma_plane(&red_difference, width, height, subsampled);
+
+    let mut color_config = av1_config(width, height, quality, options.speed);
+    color_config.chroma_sampling = if subsampled { ChromaSampling::Cs420 } else { ChromaSampling::Cs444 };
+    color_config.color_description = Some(ColorDescription {
+        color_primaries: ColorPrimaries::BT709,
+        transfer_characteristics: TransferCharacteristics::SRGB,
+        matrix_coefficients: MatrixCoefficients::BT601,
+    });
+    let color = encode_av1_frame(
+        color_config,
+        &[(&luma, width), (&blue_difference, chroma_width), (&red_difference, chroma_width)],
+    )?;
+
+    let alpha = if image.color().has_alpha() {
+        let alpha: Vec<u8> = rgba.pixels().map(|pixel| pixel.0[3]).collect();
+        let mut alpha_config = av1_config(width, height, quality, options.speed);
+        alpha_config.chroma_sampling = ChromaSampling::Cs400;
+        Some(encode_av1_frame(alpha_config, &[(&alpha, width)])?)
+    } else {
+        None
+    };
+
+    let mut avif = avif_serialize::Aviffy::new();
+    avif.set_full_color_range(true)
+        .set_chroma_subsampling((subsampled, subsampled))
+        .set_color_primaries(avif_serialize::constants::ColorPrimaries::Bt709)
+        .set_transfer_characteristics(avif_serialize::constants::TransferCharacteristics::Srgb)
+        .set_matrix_coefficients(avif_serialize::constants::MatrixCoefficients::Bt601);
+
+    Ok(avif.to_vec(&color, alpha.as_deref(), width as u32, height as u32, 8))
+}
+
+/// Round a chroma plane to 8 bits, averaging 2x2 blocks when subsampled
+fn chroma_plane(values: &[f32], width: usize, height: usize, subsampled: bool) -> Vec<u8> {
+    if !subsampled {
+        return values.iter().map(|value| value.round() as u8).collect();
+    }
+
+    let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+    let mut plane = Vec::with_capacity(chroma_width * chroma_height);
+    for chroma_y in 0..chroma_height {
+        for chroma_x in 0..chroma_width {
+            let (mut sum, mut count) = (0.0, 0.0);
+            for y in chroma_y * 2..(chroma_y * 2 + 2).min(height) {
+                for x in chroma_x * 2..(chroma_x * 2 + 2).min(width) {
+                    sum += values[y * width + x];
+                    count += 1.0;
+                }
+            }
+            plane.push((sum / count).round() as u8);
         }
     }
+    plane
+}
+
+/// Still-picture AV1 settings with a constant quantizer for `quality`
+fn av1_config(width: usize, height: usize, quality: u8, speed: u8) -> rav1e::EncoderConfig {
+    // Same curve as ravif: gentle above quality 85, where artifacts show first
+    let q = f32::from(quality.clamp(1, 100)) / 100.0;
+    let x = if q >= 0.85 {
+        (1.0 - q) * 3.0
+    } else if q > 0.25 {
+        0.875 - q * 0.5
+    } else {
+        1.0 - q
+    };
+    let quantizer = (x * 255.0).round() as u8;
+
+    rav1e::EncoderConfig {
+        width,
+        height,
+        bit_depth: 8,
+        pixel_range: rav1e::prelude::PixelRange::Full,
+        still_picture: true,
+        quantizer: usize::from(quantizer),
+        min_quantizer: quantizer,
+        ..rav1e::EncoderConfig::with_speed_preset(speed.min(10))
+    }
+}
+
+/// Encode one frame from raw 8-bit planes given as (data, stride) pairs
+fn encode_av1_frame(config: rav1e::EncoderConfig, planes: &[(&[u8], usize)]) -> Result<Vec<u8>> {
+    use rav1e::prelude::EncoderStatus;
+
+    let mut context: rav1e::Context<u8> = rav1e::Config::new()
+        .with_encoder_config(config)
+        .new_context()
+        .map_err(|e| avif_error(&e.to_string()))?;
+
+    let mut frame = context.new_frame();
+    for (plane, (data, stride)) in frame.planes.iter_mut().zip(planes) {
+        plane.copy_from_raw_u8(data, *stride, 1);
+    }
+    context.send_frame(frame).map_err(|e| avif_error(&e.to_string()))?;
+    context.flush();
+
+    let mut encoded = Vec::new();
+    loop {
+        match context.receive_packet() {
+            Ok(mut packet) => encoded.append(&mut packet.data),
+            Err(EncoderStatus::Encoded) => continue,
+            Err(EncoderStatus::LimitReached) => break,
+            Err(e) => return Err(avif_error(&e.to_string())),
+        }
+    }
+    Ok(encoded)
+}
+
+fn avif_error(message: &str) -> FastResizeError {
+    encoding_error(image::ImageFormat::Avif, message)
+}
+
+pub(crate) fn encoding_error(format: image::ImageFormat, message: &str) -> FastResizeError {
+    image::ImageError::Encoding(image::error::EncodingError::new(
+        image::error::ImageFormatHint::Exact(format),
+        message.to_string(),
+    ))
+    .into()
 }
 
 /// Get supported input formats
@@ -95,7 +338,7 @@ pub fn supported_input_formats() -> &'static [&'static str] {
 
 /// Get supported output formats
 pub fn supported_output_formats() -> &'static [&'static str] {
-    &["jpg", "jpeg", "png", "webp", "gif", "tiff", "bmp"]
+    &["jpg", "jpeg", "png", "webp", "gif", "tiff", "bmp", "avif"]
 }
 
 /// Check if a file extension is supported for input
@@ -140,6 +383,10 @@ pub fn get_optimal_quality(format: ImageFormat, target_quality: u8) -> u8 {
             // BMP is typically uncompressed
             100 // Maximum quality
         }
+        ImageFormat::Avif => {
+            // AVIF holds up at lower settings, so trade a little quality for size
+            (target_quality.clamp(1, 100) as u16 * 9 / 10).max(1) as u8
+        }
     }
 }
 
@@ -186,6 +433,18 @@ pub fn get_size_multiplier(format: ImageFormat, quality: u8) -> f32 {
             // BMP is uncompressed
             3.00 // Much larger
         }
+        ImageFormat::Avif => {
+            // AVIF typically beats WebP at the same visual quality
+            match quality {
+                1..=20 => 0.02,
+                21..=40 => 0.05,
+                41..=60 => 0.08,
+                61..=80 => 0.14,
+                81..=90 => 0.25,
+                91..=100 => 0.40,
+                _ => 0.14,
+            }
+        }
     }
 }
 
@@ -197,14 +456,6 @@ pub struct FormatOptimization {
     pub color_space: ColorSpace,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum ColorSpace {
-    Srgb,
-    AdobeRgb,
-    DisplayP3,
-    Auto,
-}
-
 impl FormatOptimization {
     /// Get optimization settings for a specific format and use case
     pub fn for_format(format: ImageFormat, web_optimized: bool) -> Self {
@@ -221,7 +472,7 @@ impl FormatOptimization {
                 preserve_metadata: !web_optimized,
                 color_space: ColorSpace::Srgb,
             },
-            ImageFormat::WebP => Self {
+            ImageFormat::WebP | ImageFormat::Avif => Self {
                 progressive: false,
                 optimize_for_web: true,
                 preserve_metadata: !web_optimized,
@@ -280,6 +531,92 @@ mod tests {
             detect_format_from_header(webp_header).unwrap(),
             ImageFormat::WebP
         );
+
+        // AVIF header, by major brand and by compatible brand
+        let avif_header = b"\x00\x00\x00\x1cftypavif\x00\x00\x00\x00avifmif1miaf";
+        assert_eq!(
+            detect_format_from_header(avif_header).unwrap(),
+            ImageFormat::Avif
+        );
+        let avif_header = b"\x00\x00\x00\x18ftypmif1\x00\x00\x00\x00mif1avif";
+        assert_eq!(
+            detect_format_from_header(avif_header).unwrap(),
+            ImageFormat::Avif
+        );
+
+        // Other ISO BMFF files (HEIC) are not AVIF
+        let heic_header = b"\x00\x00\x00\x18ftypheic\x00\x00\x00\x00mif1heic";
+        assert!(detect_format_from_header(heic_header).is_err());
+    }
+
+    #[test]
+    fn test_encode_image_round_trip() {
+        let image = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(32, 24, |x, y| {
+            image::Rgb([(x * 8) as u8, (y * 10) as u8, 128])
+        }));
+
+        for format in [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP, ImageFormat::Gif, ImageFormat::Tiff, ImageFormat::Bmp] {
+            let data = encode_image(&image, format, &ResizeConfig::new().quality(80)).unwrap();
+            assert_eq!(detect_format_from_header(&data).unwrap(), format);
+
+            let decoded = image::load_from_memory(&data).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (32, 24));
+        }
+    }
+
+    #[test]
+    fn test_webp_encoding() {
+        // Noisy gradient with a transparent left half
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::from_fn(128, 96, |x, y| {
+            let alpha = if x < 64 { 0 } else { 255 };
+            image::Rgba([(x * 2) as u8, (y * 2) as u8, ((x * 7 + y * 13) % 256) as u8, alpha])
+        }));
+
+        let low = encode_image(&image, ImageFormat::WebP, &ResizeConfig::new().quality(20)).unwrap();
+        let high = encode_image(&image, ImageFormat::WebP, &ResizeConfig::new().quality(95)).unwrap();
+        assert!(low.len() < high.len(), "quality is ignored: {} vs {}", low.len(), high.len());
+
+        // Alpha survives lossy encoding
+        let decoded = image::load_from_memory(&low).unwrap().to_rgba8();
+        assert_eq!(decoded.get_pixel(10, 10).0[3], 0);
+        assert_eq!(decoded.get_pixel(100, 10).0[3], 255);
+
+        // Lossless output round-trips exactly
+        let config = ResizeConfig::new().webp(WebPOptions::lossless());
+        let lossless = encode_image(&image, ImageFormat::WebP, &config).unwrap();
+        let decoded = image::load_from_memory(&lossless).unwrap().to_rgba8();
+        let original = image.to_rgba8();
+        for (decoded, original) in decoded.pixels().zip(original.pixels()) {
+            if original.0[3] == 255 {
+                assert_eq!(decoded, original);
+            }
+        }
+    }
+
+    #[test]
+    fn test_avif_encoding() {
+        let image = image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(64, 48, |x, y| {
+            image::Rgb([(x * 4) as u8, (y * 5) as u8, ((x * 7 + y * 13) % 256) as u8])
+        }));
+        let config = |quality, avif| ResizeConfig::new().quality(quality).avif(avif);
+        let fast = AvifOptions { speed: 10, ..Default::default() };
+
+        let low = encode_image(&image, ImageFormat::Avif, &config(20, fast)).unwrap();
+        let high = encode_image(&image, ImageFormat::Avif, &config(95, fast)).unwrap();
+        assert_eq!(detect_format_from_header(&low).unwrap(), ImageFormat::Avif);
+        assert!(low.len() < high.len(), "quality is ignored: {} vs {}", low.len(), high.len());
+
+        // Full-resolution chroma costs more bytes
+        let full_chroma = AvifOptions { subsampling: ChromaSubsampling::Yuv444, ..fast };
+        let yuv444 = encode_image(&image, ImageFormat::Avif, &config(95, full_chroma)).unwrap();
+        assert!(high.len() < yuv444.len(), "subsampling is ignored: {} vs {}", high.len(), yuv444.len());
+
+        // Alpha is stored as an extra item; odd sizes are fine with subsampling
+        let transparent = image::DynamicImage::ImageRgba8(image::RgbaImage::from_fn(33, 17, |x, _| {
+            image::Rgba([200, 100, 50, if x < 16 { 0 } else { 255 }])
+        }));
+        let with_alpha = encode_image(&transparent, ImageFormat::Avif, &config(80, fast)).unwrap();
+        assert!(with_alpha.windows(4).any(|w| w == b"auxC"));
     }
 
     #[test]
@@ -289,6 +626,7 @@ mod tests {
         assert!(!is_supported_input_format("xyz"));
 
         assert!(is_supported_output_format("webp"));
+        assert!(is_supported_output_format("avif"));
         assert!(!is_supported_output_format("raw"));
     }
 
@@ -297,6 +635,8 @@ mod tests {
         assert_eq!(get_optimal_quality(ImageFormat::Jpeg, 85), 85);
         assert_eq!(get_optimal_quality(ImageFormat::Png, 85), 15); // Inverse for compression
         assert_eq!(get_optimal_quality(ImageFormat::Gif, 85), 100); // Fixed
+        assert!(get_optimal_quality(ImageFormat::Avif, 85) < 85);
+        assert_eq!(get_optimal_quality(ImageFormat::Avif, 1), 1);
     }
 
     #[test]
@@ -311,6 +651,12 @@ mod tests {
             get_size_multiplier(ImageFormat::Jpeg, 80)
         );
         
+        // AVIF should be more efficient than WebP
+        assert!(
+            get_size_multiplier(ImageFormat::Avif, 80) <
+            get_size_multiplier(ImageFormat::WebP, 80)
+        );
+        
         // BMP should be largest
         assert!(get_size_multiplier(ImageFormat::Bmp, 100) > 1.0);
     }
@@ -0,0 +1,1243 @@
+//! EXIF, XMP, IPTC, ICC profile and pixel density passthrough
+
+use std::io::{Cursor, Read, Write};
+
+use exif::{Context, Field, In, Tag, Value};
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+use quick_xml::reader::NsReader;
+use tracing::debug;
+
+use crate::config::{ImageFormat, MetadataPolicy};
+use crate::error::Result;
+use crate::processing::formats::encoding_error;
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const PHOTOSHOP_HEADER: &[u8] = b"Photoshop 3.0\0";
+const ICC_HEADER: &[u8] = b"ICC_PROFILE\0";
+const JFIF_HEADER: &[u8] = b"JFIF\0";
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+const PNG_IPTC_KEYWORD: &[u8] = b"Raw profile type iptc";
+
+/// Photoshop image resource holding IPTC-IIM data
+const IPTC_RESOURCE: u16 = 0x0404;
+
+/// Largest ICC chunk in a JPEG APP2 segment (segment limit minus headers)
+const MAX_ICC_CHUNK: usize = 65519;
+
+/// TIFF tags holding XMP, IPTC and ICC blocks
+const TIFF_XMP: u16 = 700;
+const TIFF_IPTC: u16 = 33723;
+const TIFF_ICC: u16 = 34675;
+
+/// TIFF-context tags that describe the photo rather than the pixel layout
+const DESCRIPTIVE_TIFF_TAGS: &[Tag] = &[
+    Tag::ImageDescription,
+    Tag::Make,
+    Tag::Model,
+    Tag::Orientation,
+    Tag::XResolution,
+    Tag::YResolution,
+    Tag::ResolutionUnit,
+    Tag::Software,
+    Tag::DateTime,
+    Tag::Artist,
+    Tag::Copyright,
+];
+
+const RDF_NS: &[u8] = b"http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XML_NS: &[u8] = b"http://www.w3.org/XML/1998/namespace";
+const DC_NS: &[u8] = b"http://purl.org/dc/elements/1.1/";
+const XMP_RIGHTS_NS: &[u8] = b"http://ns.adobe.com/xap/1.0/rights/";
+const PHOTOSHOP_NS: &[u8] = b"http://ns.adobe.com/photoshop/1.0/";
+const EXIF_NS: &[u8] = b"http://ns.adobe.com/exif/1.0/";
+const TIFF_NS: &[u8] = b"http://ns.adobe.com/tiff/1.0/";
+
+/// Metadata blocks carried from an input file to its output
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// EXIF as a TIFF structure, without the `Exif\0\0` prefix
+    pub exif: Option<Vec<u8>>,
+    /// XMP packet
+    pub xmp: Option<Vec<u8>>,
+    /// IPTC-IIM datasets
+    pub iptc: Option<Vec<u8>>,
+    /// ICC color profile, which the metadata policy leaves alone
+    pub icc: Option<Vec<u8>>,
+    /// Pixel density in dots per inch, which the metadata policy leaves alone
+    pub dpi: Option<f32>,
+}
+
+/// Whether a format can carry metadata and ICC profiles in this crate
+pub fn embeds_metadata(format: ImageFormat) -> bool {
+    matches!(format, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Tiff)
+}
+
+/// Whether a format has a pixel density field of its own
+pub fn embeds_density(format: ImageFormat) -> bool {
+    matches!(format, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Tiff)
+}
+
+impl Metadata {
+    /// Extract metadata from an encoded JPEG, PNG, WebP or TIFF file
+    ///
+    /// Blocks that are missing or malformed are left out; other formats
+    /// yield no metadata. The density comes from the JFIF header or pHYs
+    /// chunk, falling back to the EXIF resolution.
+    pub fn read(data: &[u8], format: ImageFormat) -> Self {
+        let mut metadata = match format {
+            ImageFormat::Jpeg => read_jpeg(data),
+            ImageFormat::Png => read_png(data),
+            ImageFormat::WebP => read_webp(data),
+            ImageFormat::Tiff => read_tiff(data),
+            _ => Self::default(),
+        };
+        if metadata.dpi.is_none() {
+            metadata.dpi = metadata.exif.as_deref().and_then(exif_dpi);
+        }
+        metadata
+    }
+
+    /// Whether there is nothing to write
+    pub fn is_empty(&self) -> bool {
+        self.exif.is_none() && self.xmp.is_none() && self.iptc.is_none() && self.icc.is_none() && self.dpi.is_none()
+    }
+
+    /// Set the pixel density, updating carried EXIF resolution tags to match
+    pub fn with_dpi(self, dpi: f32) -> Self {
+        let exif = match self.exif {
+            Some(exif) if exif_dpi(&exif).is_some_and(|current| (current - dpi).abs() > 0.01) => {
+                set_exif_dpi(exif, dpi)
+            }
+            exif => exif,
+        };
+        Self { exif, dpi: Some(dpi), ..self }
+    }
+
+    /// Keep what the policy allows
+    ///
+    /// `reset_orientation` marks images already turned upright, whose
+    /// orientation tags are set back to normal.
+    pub fn filter(self, policy: MetadataPolicy, reset_orientation: bool) -> Self {
+        if policy == MetadataPolicy::StripAll {
+            return Self { icc: self.icc, dpi: self.dpi, ..Self::default() };
+        }
+
+        Self {
+            exif: self.exif.and_then(|exif| filter_exif(exif, policy, reset_orientation)),
+            xmp: self.xmp.and_then(|xmp| filter_xmp(xmp, policy, reset_orientation)),
+            iptc: self.iptc.and_then(|iptc| filter_iptc(iptc, policy)),
+            icc: self.icc,
+            dpi: self.dpi,
+        }
+    }
+
+    /// Write the metadata into an encoded image
+    ///
+    /// JPEG, PNG and TIFF take every block and the density, WebP all but IPTC
+    /// and the density. Other formats are returned unchanged.
+    pub fn embed(&self, encoded: Vec<u8>, format: ImageFormat) -> Result<Vec<u8>> {
+        if self.is_empty() {
+            return Ok(encoded);
+        }
+
+        match format {
+            ImageFormat::Jpeg => Ok(embed_jpeg(encoded, self)),
+            ImageFormat::Png => Ok(embed_png(encoded, self)),
+            ImageFormat::WebP => Ok(embed_webp(encoded, self)),
+            ImageFormat::Tiff => embed_tiff(&encoded, self),
+            _ => {
+                debug!("{:?} output cannot carry metadata, writing without it", format);
+                Ok(encoded)
+            }
+        }
+    }
+}
+
+fn filter_exif(exif: Vec<u8>, policy: MetadataPolicy, reset_orientation: bool) -> Option<Vec<u8>> {
+    let Ok(parsed) = exif::Reader::new().read_raw(exif.clone()) else {
+        // Unreadable EXIF can only be passed through untouched
+        return (policy == MetadataPolicy::KeepAll && !reset_orientation).then_some(exif);
+    };
+
+    let fields: Vec<&Field> = parsed.fields().filter(|f| f.ifd_num == In::PRIMARY).collect();
+    let reset_orientation = reset_orientation && fields.iter()
+        .any(|f| f.tag == Tag::Orientation && f.value.get_uint(0) != Some(1));
+    let has_gps = fields.iter().any(|f| f.tag.context() == Context::Gps);
+
+    // Untouched EXIF keeps maker notes and thumbnails intact
+    match policy {
+        MetadataPolicy::KeepAll if !reset_orientation => return Some(exif),
+        MetadataPolicy::StripGps if !reset_orientation && !has_gps => return Some(exif),
+        _ => {}
+    }
+
+    let kept: Vec<Field> = fields.into_iter()
+        .filter(|f| match policy {
+            MetadataPolicy::StripAll => false,
+            MetadataPolicy::KeepAll => true,
+            MetadataPolicy::StripGps => f.tag.context() != Context::Gps,
+            // Orientation stays so images that were not turned upright still display correctly
+            MetadataPolicy::CopyrightOnly => {
+                matches!(f.tag, Tag::Artist | Tag::Copyright | Tag::Orientation)
+            }
+        })
+        .map(|f| match f.tag {
+            Tag::Orientation if reset_orientation => Field {
+                tag: f.tag,
+                ifd_num: f.ifd_num,
+                value: Value::Short(vec![1]),
+            },
+            _ => f.clone(),
+        })
+        .collect();
+
+    if !kept.iter().any(|f| f.tag != Tag::Orientation || f.value.get_uint(0) != Some(1)) {
+        return None;
+    }
+    write_exif(&kept, parsed.little_endian())
+}
+
+/// Serialize EXIF fields of the primary image into a TIFF structure
+fn write_exif(fields: &[Field], little_endian: bool) -> Option<Vec<u8>> {
+    let mut writer = exif::experimental::Writer::new();
+    let mut written = 0;
+    for field in fields {
+        if !matches!(field.value, Value::Unknown(..)) {
+            writer.push_field(field);
+            written += 1;
+        }
+    }
+    if written == 0 {
+        return None;
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    match writer.write(&mut buffer, little_endian) {
+        Ok(()) => Some(buffer.into_inner()),
+        Err(e) => {
+            debug!("Dropping EXIF that cannot be rewritten: {}", e);
+            None
+        }
+    }
+}
+
+/// Resolution in dots per inch from the EXIF XResolution and ResolutionUnit
+fn exif_dpi(exif: &[u8]) -> Option<f32> {
+    let parsed = exif::Reader::new().read_raw(exif.to_vec()).ok()?;
+    let resolution = match &parsed.get_field(Tag::XResolution, In::PRIMARY)?.value {
+        Value::Rational(values) => values.first()?.to_f64(),
+        _ => return None,
+    };
+    let unit = parsed.get_field(Tag::ResolutionUnit, In::PRIMARY)
+        .and_then(|field| field.value.get_uint(0))
+        .unwrap_or(2);
+    let dpi = match unit {
+        2 => resolution,
+        3 => resolution * 2.54,
+        // No absolute unit, only an aspect ratio
+        _ => return None,
+    };
+    (dpi.is_finite() && dpi > 0.0).then_some(dpi as f32)
+}
+
+/// EXIF resolution fields for a density in dots per inch
+fn resolution_fields(dpi: f32) -> [Field; 3] {
+    let resolution = Value::Rational(vec![exif::Rational::from(((dpi * 100.0).round() as u32, 100))]);
+    [
+        Field { tag: Tag::XResolution, ifd_num: In::PRIMARY, value: resolution.clone() },
+        Field { tag: Tag::YResolution, ifd_num: In::PRIMARY, value: resolution },
+        Field { tag: Tag::ResolutionUnit, ifd_num: In::PRIMARY, value: Value::Short(vec![2]) },
+    ]
+}
+
+fn is_resolution_tag(tag: Tag) -> bool {
+    matches!(tag, Tag::XResolution | Tag::YResolution | Tag::ResolutionUnit)
+}
+
+fn set_exif_dpi(exif: Vec<u8>, dpi: f32) -> Option<Vec<u8>> {
+    let Ok(parsed) = exif::Reader::new().read_raw(exif.clone()) else {
+        return Some(exif);
+    };
+    let mut fields: Vec<Field> = parsed.fields()
+        .filter(|f| f.ifd_num == In::PRIMARY && !is_resolution_tag(f.tag))
+        .cloned()
+        .collect();
+    fields.extend(resolution_fields(dpi));
+    write_exif(&fields, parsed.little_endian())
+}
+
+fn filter_xmp(xmp: Vec<u8>, policy: MetadataPolicy, reset_orientation: bool) -> Option<Vec<u8>> {
+    if policy == MetadataPolicy::KeepAll && !reset_orientation {
+        return Some(xmp);
+    }
+
+    let keep = |ns: &[u8], local: &[u8]| {
+        let orientation = ns == TIFF_NS && local == b"Orientation";
+        if orientation && reset_orientation {
+            return false;
+        }
+        match policy {
+            MetadataPolicy::StripAll => false,
+            MetadataPolicy::KeepAll => true,
+            MetadataPolicy::StripGps => !(ns == EXIF_NS && local.starts_with(b"GPS")),
+            MetadataPolicy::CopyrightOnly => {
+                orientation
+                    || ns == XMP_RIGHTS_NS
+                    || (ns == DC_NS && matches!(local, b"creator" | b"rights"))
+                    || (ns == PHOTOSHOP_NS && matches!(local, b"AuthorsPosition" | b"Credit"))
+            }
+        }
+    };
+
+    let filtered = rewrite_xmp(&xmp, keep);
+    if filtered.is_none() {
+        debug!("Dropping XMP that cannot be filtered");
+    }
+    filtered
+}
+
+/// Copy an XMP packet, leaving out properties rejected by `keep`
+///
+/// Properties are the children and non-RDF attributes of `rdf:Description`,
+/// matched by namespace URI and local name. Returns `None` when the packet
+/// is malformed or no property is left.
+fn rewrite_xmp(xmp: &[u8], keep: impl Fn(&[u8], &[u8]) -> bool) -> Option<Vec<u8>> {
+    let mut reader = NsReader::from_reader(xmp);
+    let mut writer = quick_xml::Writer::new(Vec::new());
+    let mut depth = 0;
+    let mut description = None;
+    let mut skipping = None;
+    let mut properties = 0;
+
+    loop {
+        let event = reader.read_event().ok()?;
+        match event {
+            Event::Eof => break,
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                let is_start = matches!(event, Event::Start(_));
+                let element_depth = depth + 1;
+                if is_start {
+                    depth += 1;
+                }
+                if skipping.is_some() {
+                    continue;
+                }
+
+                let (ns, local) = reader.resolve_element(element.name());
+                let ns = bound(ns);
+
+                if description.is_some_and(|d| element_depth == d + 1) {
+                    if ns.is_some_and(|ns| !keep(ns, local.as_ref())) {
+                        if is_start {
+                            skipping = Some(depth);
+                        }
+                        continue;
+                    }
+                    properties += 1;
+                } else if ns == Some(RDF_NS) && local.as_ref() == b"Description" {
+                    if is_start {
+                        description = Some(depth);
+                    }
+
+                    let mut filtered = element.clone();
+                    filtered.clear_attributes();
+                    for attribute in element.attributes() {
+                        let attribute = attribute.ok()?;
+                        if attribute.key.as_namespace_binding().is_none() {
+                            let (attribute_ns, attribute_local) = reader.resolve_attribute(attribute.key);
+                            match bound(attribute_ns) {
+                                Some(ns) if ns == RDF_NS || ns == XML_NS => {}
+                                Some(ns) if !keep(ns, attribute_local.as_ref()) => continue,
+                                _ => properties += 1,
+                            }
+                        }
+                        filtered.push_attribute(attribute);
+                    }
+
+                    let filtered = if is_start { Event::Start(filtered) } else { Event::Empty(filtered) };
+                    writer.write_event(filtered).ok()?;
+                    continue;
+                }
+
+                writer.write_event(event.borrow()).ok()?;
+            }
+            Event::End(_) => {
+                depth -= 1;
+                if let Some(skip_depth) = skipping {
+                    if depth < skip_depth {
+                        skipping = None;
+                    }
+                    continue;
+                }
+                if description.is_some_and(|d| depth < d) {
+                    description = None;
+                }
+                writer.write_event(event).ok()?;
+            }
+            event => {
+                if skipping.is_none() {
+                    writer.write_event(event).ok()?;
+                }
+            }
+        }
+    }
+
+    (properties > 0).then(|| writer.into_inner())
+}
+
+fn bound(ns: ResolveResult<'_>) -> Option<&[u8]> {
+    match ns {
+        ResolveResult::Bound(ns) => Some(ns.into_inner()),
+        _ => None,
+    }
+}
+
+fn filter_iptc(iptc: Vec<u8>, policy: MetadataPolicy) -> Option<Vec<u8>> {
+    match policy {
+        MetadataPolicy::StripAll => None,
+        // IPTC-IIM has no GPS datasets
+        MetadataPolicy::KeepAll | MetadataPolicy::StripGps => Some(iptc),
+        MetadataPolicy::CopyrightOnly => {
+            let mut kept = Vec::new();
+            let mut has_rights = false;
+            for (record, dataset, raw) in iim_datasets(&iptc) {
+                // By-line, By-line Title, Credit, Source, Copyright Notice
+                let rights = record == 2 && matches!(dataset, 80 | 85 | 110 | 115 | 116);
+                // Coded character set and record version
+                let structural = (record, dataset) == (1, 90) || (record, dataset) == (2, 0);
+                if rights || structural {
+                    kept.extend_from_slice(raw);
+                }
+                has_rights |= rights;
+            }
+            has_rights.then_some(kept)
+        }
+    }
+}
+
+/// IPTC-IIM datasets as (record, dataset, raw bytes including the header)
+fn iim_datasets(data: &[u8]) -> Vec<(u8, u8, &[u8])> {
+    let mut datasets = Vec::new();
+    let mut pos = 0;
+
+    while pos + 5 <= data.len() && data[pos] == 0x1C {
+        let (record, dataset) = (data[pos + 1], data[pos + 2]);
+        let length = u16::from_be_bytes([data[pos + 3], data[pos + 4]]) as usize;
+        let (header, length) = if length & 0x8000 == 0 {
+            (5, length)
+        } else {
+            // Extended dataset: the low bits give the size of the length field
+            let size = length & 0x7FFF;
+            if size > 4 || pos + 5 + size > data.len() {
+                break;
+            }
+            let length = data[pos + 5..pos + 5 + size].iter()
+                .fold(0usize, |length, &byte| (length << 8) | byte as usize);
+            (5 + size, length)
+        };
+
+        let end = pos + header + length;
+        if end > data.len() {
+            break;
+        }
+        datasets.push((record, dataset, &data[pos..end]));
+        pos = end;
+    }
+
+    datasets
+}
+
+/// Pull the IPTC-IIM resource out of Photoshop image resources
+fn photoshop_iptc(resources: &[u8]) -> Option<Vec<u8>> {
+    let mut pos = 0;
+
+    while pos + 12 <= resources.len() && &resources[pos..pos + 4] == b"8BIM" {
+        let id = u16::from_be_bytes([resources[pos + 4], resources[pos + 5]]);
+        // Pascal string name, padded to an even length
+        let name_length = (resources[pos + 6] as usize + 2) & !1;
+        let size_at = pos + 6 + name_length;
+        let size = u32::from_be_bytes(resources.get(size_at..size_at + 4)?.try_into().ok()?) as usize;
+        let start = size_at + 4;
+        let data = resources.get(start..start + size)?;
+        if id == IPTC_RESOURCE {
+            return Some(data.to_vec());
+        }
+        pos = start + size + (size & 1);
+    }
+
+    None
+}
+
+/// Wrap IPTC-IIM data in a single Photoshop image resource
+fn photoshop_resource(iptc: &[u8]) -> Vec<u8> {
+    let mut resource = b"8BIM".to_vec();
+    resource.extend_from_slice(&IPTC_RESOURCE.to_be_bytes());
+    resource.extend_from_slice(&[0, 0]);
+    resource.extend_from_slice(&(iptc.len() as u32).to_be_bytes());
+    resource.extend_from_slice(iptc);
+    if iptc.len() % 2 == 1 {
+        resource.push(0);
+    }
+    resource
+}
+
+fn strip_exif_header(exif: &[u8]) -> Vec<u8> {
+    exif.strip_prefix(EXIF_HEADER).unwrap_or(exif).to_vec()
+}
+
+/// JPEG marker segments before the first scan as (marker, offset, payload)
+fn jpeg_segments(data: &[u8]) -> Vec<(u8, usize, &[u8])> {
+    let mut segments = Vec::new();
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return segments;
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF {
+        let marker = data[pos + 1];
+        match marker {
+            // Fill byte
+            0xFF => pos += 1,
+            // Start of scan or end of image
+            0xDA | 0xD9 => break,
+            // Markers without a payload
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            _ => {
+                let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+                if length < 2 || pos + 2 + length > data.len() {
+                    break;
+                }
+                segments.push((marker, pos, &data[pos + 4..pos + 2 + length]));
+                pos += 2 + length;
+            }
+        }
+    }
+
+    segments
+}
+
+fn read_jpeg(data: &[u8]) -> Metadata {
+    let mut metadata = Metadata::default();
+    let mut icc_chunks = Vec::new();
+
+    for (marker, _, payload) in jpeg_segments(data) {
+        match marker {
+            0xE1 if metadata.exif.is_none() && payload.starts_with(EXIF_HEADER) => {
+                metadata.exif = Some(payload[EXIF_HEADER.len()..].to_vec());
+            }
+            0xE1 if metadata.xmp.is_none() && payload.starts_with(XMP_HEADER) => {
+                metadata.xmp = Some(payload[XMP_HEADER.len()..].to_vec());
+            }
+            0xED if metadata.iptc.is_none() && payload.starts_with(PHOTOSHOP_HEADER) => {
+                metadata.iptc = photoshop_iptc(&payload[PHOTOSHOP_HEADER.len()..]);
+            }
+            0xE0 if metadata.dpi.is_none() && payload.starts_with(JFIF_HEADER) => {
+                metadata.dpi = jfif_dpi(payload);
+            }
+            // Sequence number, chunk count, then a slice of the profile
+            0xE2 if payload.len() > ICC_HEADER.len() + 2 && payload.starts_with(ICC_HEADER) => {
+                let sequence = payload[ICC_HEADER.len()];
+                icc_chunks.push((sequence, &payload[ICC_HEADER.len() + 2..]));
+            }
+            _ => {}
+        }
+    }
+
+    if !icc_chunks.is_empty() {
+        icc_chunks.sort_by_key(|&(sequence, _)| sequence);
+        metadata.icc = Some(icc_chunks.into_iter().flat_map(|(_, chunk)| chunk.iter().copied()).collect());
+    }
+
+    metadata
+}
+
+fn embed_jpeg(mut jpeg: Vec<u8>, metadata: &Metadata) -> Vec<u8> {
+    let mut segments = Vec::new();
+    if let Some(exif) = &metadata.exif {
+        push_jpeg_segment(&mut segments, 0xE1, &[EXIF_HEADER, exif]);
+    }
+    if let Some(icc) = &metadata.icc {
+        let count = icc.len().div_ceil(MAX_ICC_CHUNK);
+        match u8::try_from(count) {
+            Ok(count) => {
+                for (index, chunk) in icc.chunks(MAX_ICC_CHUNK).enumerate() {
+                    push_jpeg_segment(&mut segments, 0xE2, &[ICC_HEADER, &[index as u8 + 1, count], chunk]);
+                }
+            }
+            Err(_) => debug!("ICC profile of {} bytes does not fit a JPEG, dropping it", icc.len()),
+        }
+    }
+    if let Some(xmp) = &metadata.xmp {
+        push_jpeg_segment(&mut segments, 0xE1, &[XMP_HEADER, xmp]);
+    }
+    if let Some(iptc) = &metadata.iptc {
+        push_jpeg_segment(&mut segments, 0xED, &[PHOTOSHOP_HEADER, &photoshop_resource(iptc)]);
+    }
+
+    // After SOI and the JFIF header, which must come first
+    let (insert_at, jfif) = match jpeg_segments(&jpeg).first() {
+        Some(&(0xE0, offset, payload)) => {
+            let jfif = payload.starts_with(JFIF_HEADER) && payload.len() >= 12;
+            (offset + 4 + payload.len(), jfif.then_some(offset + 4))
+        }
+        Some(_) => (2, None),
+        None => return jpeg,
+    };
+
+    // Density lives in the JFIF header: units, then horizontal and vertical
+    let mut header = Vec::new();
+    if let Some(dpi) = metadata.dpi {
+        let density = (dpi.round().clamp(1.0, 65535.0) as u16).to_be_bytes();
+        match jfif {
+            Some(start) => {
+                jpeg[start + 7] = 1;
+                jpeg[start + 8..start + 10].copy_from_slice(&density);
+                jpeg[start + 10..start + 12].copy_from_slice(&density);
+            }
+            // Version 1.02, dots per inch, no thumbnail
+            None => push_jpeg_segment(&mut header, 0xE0, &[JFIF_HEADER, &[1, 2, 1], &density, &density, &[0, 0]]),
+        }
+    }
+
+    let mut output = Vec::with_capacity(jpeg.len() + header.len() + segments.len());
+    output.extend_from_slice(&jpeg[..insert_at]);
+    output.extend_from_slice(&header);
+    output.extend_from_slice(&segments);
+    output.extend_from_slice(&jpeg[insert_at..]);
+    output
+}
+
+/// Dots per inch from a JFIF APP0 payload with absolute units
+fn jfif_dpi(payload: &[u8]) -> Option<f32> {
+    let density = f32::from(u16::from_be_bytes([*payload.get(8)?, *payload.get(9)?]));
+    match payload[7] {
+        1 => Some(density),
+        2 => Some(density * 2.54),
+        // Aspect ratio only
+        _ => None,
+    }.filter(|&dpi| dpi > 0.0)
+}
+
+fn push_jpeg_segment(output: &mut Vec<u8>, marker: u8, parts: &[&[u8]]) {
+    let length = parts.iter().map(|part| part.len()).sum::<usize>() + 2;
+    let Ok(length) = u16::try_from(length) else {
+        debug!("Metadata block of {} bytes does not fit a JPEG segment, dropping it", length);
+        return;
+    };
+
+    output.extend_from_slice(&[0xFF, marker]);
+    output.extend_from_slice(&length.to_be_bytes());
+    for part in parts {
+        output.extend_from_slice(part);
+    }
+}
+
+/// PNG chunks as (type, offset, data)
+fn png_chunks(data: &[u8]) -> Vec<([u8; 4], usize, &[u8])> {
+    let mut chunks = Vec::new();
+    if !data.starts_with(PNG_SIGNATURE) {
+        return chunks;
+    }
+
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 12 <= data.len() {
+        let length = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let chunk_type = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let Some(chunk) = data.get(pos + 8..pos + 8 + length) else {
+            break;
+        };
+        chunks.push((chunk_type, pos, chunk));
+        pos += 12 + length;
+    }
+
+    chunks
+}
+
+fn read_png(data: &[u8]) -> Metadata {
+    let mut metadata = Metadata::default();
+
+    for (chunk_type, _, chunk) in png_chunks(data) {
+        match &chunk_type {
+            b"eXIf" => metadata.exif = Some(strip_exif_header(chunk)),
+            // Pixels per unit on each axis, then the unit; 1 is the meter
+            b"pHYs" if chunk.len() == 9 && chunk[8] == 1 => {
+                let pixels_per_meter = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                // Whole meters blur the DPI, so keep one decimal
+                let dpi = (pixels_per_meter as f32 * 0.254).round() / 10.0;
+                metadata.dpi = Some(dpi).filter(|&dpi| dpi > 0.0);
+            }
+            // Profile name, compression method, then zlib data
+            b"iCCP" => {
+                metadata.icc = chunk.iter()
+                    .position(|&b| b == 0)
+                    .and_then(|split| chunk.get(split + 2..))
+                    .and_then(inflate);
+            }
+            b"iTXt" => {
+                if let Some(text) = png_international_text(chunk, PNG_XMP_KEYWORD) {
+                    metadata.xmp = Some(text);
+                }
+            }
+            b"tEXt" | b"zTXt" => {
+                let Some(split) = chunk.iter().position(|&b| b == 0) else {
+                    continue;
+                };
+                let (keyword, rest) = (&chunk[..split], &chunk[split + 1..]);
+                if !keyword.starts_with(b"Raw profile type ") {
+                    continue;
+                }
+                let text = if &chunk_type == b"zTXt" {
+                    // Compression method byte, then zlib data
+                    match rest.split_first().and_then(|(_, data)| inflate(data)) {
+                        Some(text) => text,
+                        None => continue,
+                    }
+                } else {
+                    rest.to_vec()
+                };
+                let Some(profile) = decode_raw_profile(&text) else {
+                    continue;
+                };
+
+                match &keyword[b"Raw profile type ".len()..] {
+                    b"exif" | b"APP1" if metadata.exif.is_none() => {
+                        metadata.exif = Some(strip_exif_header(&profile));
+                    }
+                    b"xmp" if metadata.xmp.is_none() => metadata.xmp = Some(profile),
+                    b"iptc" if metadata.iptc.is_none() => {
+                        let resources = profile.strip_prefix(PHOTOSHOP_HEADER).unwrap_or(&profile);
+                        metadata.iptc = if resources.starts_with(b"8BIM") {
+                            photoshop_iptc(resources)
+                        } else {
+                            Some(profile)
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    metadata
+}
+
+/// Text of an iTXt chunk with the given keyword
+fn png_international_text(chunk: &[u8], keyword: &[u8]) -> Option<Vec<u8>> {
+    let rest = chunk.strip_prefix(keyword)?.strip_prefix(&[0])?;
+    let (&compressed, rest) = rest.split_first()?;
+    // Compression method, then language tag and translated keyword
+    let rest = rest.get(1..)?;
+    let language_end = rest.iter().position(|&b| b == 0)?;
+    let rest = &rest[language_end + 1..];
+    let translated_end = rest.iter().position(|&b| b == 0)?;
+    let text = &rest[translated_end + 1..];
+
+    if compressed == 1 {
+        inflate(text)
+    } else {
+        Some(text.to_vec())
+    }
+}
+
+fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    flate2::read::ZlibDecoder::new(data).read_to_end(&mut output).ok()?;
+    Some(output)
+}
+
+/// Decode an ImageMagick raw profile: a name line, a length line, then hex
+fn decode_raw_profile(text: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(text).ok()?;
+    let mut tokens = text.split_ascii_whitespace();
+    let _name = tokens.next()?;
+    let length: usize = tokens.next()?.parse().ok()?;
+
+    let hex: Vec<u8> = tokens.flat_map(str::bytes).collect();
+    if hex.len() < length * 2 {
+        return None;
+    }
+    hex.chunks(2)
+        .take(length)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+fn encode_raw_profile(name: &str, data: &[u8]) -> Vec<u8> {
+    let mut text = format!("\n{}\n{:8}\n", name, data.len());
+    for line in data.chunks(36) {
+        for byte in line {
+            text.push_str(&format!("{:02x}", byte));
+        }
+        text.push('\n');
+    }
+    text.into_bytes()
+}
+
+fn embed_png(png: Vec<u8>, metadata: &Metadata) -> Vec<u8> {
+    // Right after IHDR, ahead of the image data
+    let Some(&(_, offset, ihdr)) = png_chunks(&png).first() else {
+        return png;
+    };
+    let insert_at = offset + 12 + ihdr.len();
+
+    let mut chunks = Vec::new();
+    if let Some(icc) = &metadata.icc {
+        let mut profile = b"icc\0\0".to_vec();
+        let mut encoder = flate2::write::ZlibEncoder::new(&mut profile, flate2::Compression::default());
+        if encoder.write_all(icc).and_then(|()| encoder.finish().map(|_| ())).is_ok() {
+            push_png_chunk(&mut chunks, b"iCCP", &profile);
+        }
+    }
+    if let Some(dpi) = metadata.dpi {
+        let pixels_per_meter = ((dpi / 0.0254).round() as u32).to_be_bytes();
+        let mut density = [0; 9];
+        density[..4].copy_from_slice(&pixels_per_meter);
+        density[4..8].copy_from_slice(&pixels_per_meter);
+        density[8] = 1;
+        push_png_chunk(&mut chunks, b"pHYs", &density);
+    }
+    if let Some(exif) = &metadata.exif {
+        push_png_chunk(&mut chunks, b"eXIf", exif);
+    }
+    if let Some(xmp) = &metadata.xmp {
+        let mut text = PNG_XMP_KEYWORD.to_vec();
+        // Null separator, uncompressed, no language tag or translated keyword
+        text.extend_from_slice(&[0, 0, 0, 0, 0]);
+        text.extend_from_slice(xmp);
+        push_png_chunk(&mut chunks, b"iTXt", &text);
+    }
+    if let Some(iptc) = &metadata.iptc {
+        let mut text = PNG_IPTC_KEYWORD.to_vec();
+        text.push(0);
+        text.extend_from_slice(&encode_raw_profile("iptc", iptc));
+        push_png_chunk(&mut chunks, b"tEXt", &text);
+    }
+
+    let mut output = Vec::with_capacity(png.len() + chunks.len());
+    output.extend_from_slice(&png[..insert_at]);
+    output.extend_from_slice(&chunks);
+    output.extend_from_slice(&png[insert_at..]);
+    output
+}
+
+fn push_png_chunk(output: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(chunk_type);
+    crc.update(data);
+
+    output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    output.extend_from_slice(chunk_type);
+    output.extend_from_slice(data);
+    output.extend_from_slice(&crc.finalize().to_be_bytes());
+}
+
+/// WebP (RIFF) chunks as (fourcc, offset, data)
+fn webp_chunks(data: &[u8]) -> Vec<([u8; 4], usize, &[u8])> {
+    let mut chunks = Vec::new();
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+        return chunks;
+    }
+
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let fourcc = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+        let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]) as usize;
+        let Some(chunk) = data.get(pos + 8..pos + 8 + size) else {
+            break;
+        };
+        chunks.push((fourcc, pos, chunk));
+        pos += 8 + size + (size & 1);
+    }
+
+    chunks
+}
+
+fn read_webp(data: &[u8]) -> Metadata {
+    let mut metadata = Metadata::default();
+
+    for (fourcc, _, chunk) in webp_chunks(data) {
+        match &fourcc {
+            b"EXIF" => metadata.exif = Some(strip_exif_header(chunk)),
+            b"XMP " => metadata.xmp = Some(chunk.to_vec()),
+            b"ICCP" => metadata.icc = Some(chunk.to_vec()),
+            _ => {}
+        }
+    }
+
+    metadata
+}
+
+fn embed_webp(webp: Vec<u8>, metadata: &Metadata) -> Vec<u8> {
+    const ICC: u8 = 0x20;
+    const ALPHA: u8 = 0x10;
+    const EXIF: u8 = 0x08;
+    const XMP: u8 = 0x04;
+
+    let chunks = webp_chunks(&webp);
+    let Some(&(first, _, first_data)) = chunks.first() else {
+        return webp;
+    };
+
+    let mut flags = 0;
+    if metadata.icc.is_some() {
+        flags |= ICC;
+    }
+    if metadata.exif.is_some() {
+        flags |= EXIF;
+    }
+    if metadata.xmp.is_some() {
+        flags |= XMP;
+    }
+    if flags == 0 {
+        return webp;
+    }
+
+    let mut body = b"WEBP".to_vec();
+    match &first {
+        b"VP8X" => {
+            for &(fourcc, offset, data) in &chunks {
+                if matches!(&fourcc, b"EXIF" | b"XMP " | b"ICCP") {
+                    continue;
+                }
+                let start = body.len();
+                body.extend_from_slice(&webp[offset..offset + 8 + data.len() + (data.len() & 1)]);
+                if &fourcc == b"VP8X" {
+                    body[start + 8] |= flags;
+                    // The profile must directly follow the header
+                    if let Some(icc) = &metadata.icc {
+                        push_webp_chunk(&mut body, b"ICCP", icc);
+                    }
+                }
+            }
+        }
+        // Simple formats need an extended header to carry metadata
+        b"VP8 " | b"VP8L" => {
+            let Some((width, height, alpha)) = webp_canvas(&first, first_data) else {
+                return webp;
+            };
+            let mut header = vec![flags | if alpha { ALPHA } else { 0 }, 0, 0, 0];
+            header.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+            header.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+            push_webp_chunk(&mut body, b"VP8X", &header);
+            if let Some(icc) = &metadata.icc {
+                push_webp_chunk(&mut body, b"ICCP", icc);
+            }
+            body.extend_from_slice(&webp[12..]);
+        }
+        _ => return webp,
+    }
+
+    if let Some(exif) = &metadata.exif {
+        push_webp_chunk(&mut body, b"EXIF", exif);
+    }
+    if let Some(xmp) = &metadata.xmp {
+        push_webp_chunk(&mut body, b"XMP ", xmp);
+    }
+
+    let mut output = b"RIFF".to_vec();
+    output.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    output.extend_from_slice(&body);
+    output
+}
+
+/// Canvas width, height and alpha use of a simple-format WebP bitstream
+fn webp_canvas(fourcc: &[u8; 4], data: &[u8]) -> Option<(u32, u32, bool)> {
+    match fourcc {
+        // Frame tag, start code, then 14-bit width and height
+        b"VP8 " if data.len() >= 10 => Some((
+            u32::from(u16::from_le_bytes([data[6], data[7]]) & 0x3FFF),
+            u32::from(u16::from_le_bytes([data[8], data[9]]) & 0x3FFF),
+            false,
+        )),
+        // Signature byte, then width-1 and height-1 (14 bits each) and the alpha bit
+        b"VP8L" if data.len() >= 5 && data[0] == 0x2F => {
+            let bits = u32::from_le_bytes([data[1], data[2], data[3], data[4]]);
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1, (bits >> 28) & 1 == 1))
+        }
+        _ => None,
+    }
+}
+
+fn push_webp_chunk(output: &mut Vec<u8>, fourcc: &[u8; 4], data: &[u8]) {
+    output.extend_from_slice(fourcc);
+    output.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    output.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        output.push(0);
+    }
+}
+
+fn read_tiff(data: &[u8]) -> Metadata {
+    let Ok(parsed) = exif::Reader::new().read_raw(data.to_vec()) else {
+        return Metadata::default();
+    };
+    let little_endian = parsed.little_endian();
+    let tiff_block = |number| {
+        parsed.get_field(Tag(Context::Tiff, number), In::PRIMARY)
+            .and_then(|field| value_bytes(&field.value, little_endian))
+    };
+
+    let fields: Vec<Field> = parsed.fields()
+        .filter(|f| f.ifd_num == In::PRIMARY)
+        .filter(|f| f.tag.context() != Context::Tiff || DESCRIPTIVE_TIFF_TAGS.contains(&f.tag))
+        .cloned()
+        .collect();
+
+    Metadata {
+        exif: write_exif(&fields, little_endian),
+        xmp: tiff_block(TIFF_XMP),
+        iptc: tiff_block(TIFF_IPTC),
+        icc: tiff_block(TIFF_ICC),
+        // Read from the resolution tags carried in `exif`
+        dpi: None,
+    }
+}
+
+/// Raw bytes of a TIFF field holding an opaque block
+fn value_bytes(value: &Value, little_endian: bool) -> Option<Vec<u8>> {
+    match value {
+        Value::Byte(bytes) | Value::Undefined(bytes, _) => Some(bytes.clone()),
+        Value::Ascii(strings) => Some(strings.concat()),
+        // Some writers store IPTC as LONGs
+        Value::Long(values) => Some(values.iter()
+            .flat_map(|v| if little_endian { v.to_le_bytes() } else { v.to_be_bytes() })
+            .collect()),
+        _ => None,
+    }
+}
+
+fn embed_tiff(tiff: &[u8], metadata: &Metadata) -> Result<Vec<u8>> {
+    let tiff_error = |message: String| encoding_error(image::ImageFormat::Tiff, &message);
+
+    let image = exif::Reader::new().read_raw(tiff.to_vec())
+        .map_err(|e| tiff_error(format!("Failed to reread TIFF output: {}", e)))?;
+    // A density replaces the resolution the encoder wrote
+    let replaced = |tag| metadata.dpi.is_some() && is_resolution_tag(tag);
+    let image_fields: Vec<&Field> = image.fields()
+        .filter(|f| f.ifd_num == In::PRIMARY && !replaced(f.tag))
+        .collect();
+
+    let strip_values = |tag| image.get_field(tag, In::PRIMARY).and_then(|f| f.value.iter_uint());
+    let (Some(offsets), Some(counts)) = (strip_values(Tag::StripOffsets), strip_values(Tag::StripByteCounts)) else {
+        return Err(tiff_error("TIFF output has no strips".to_string()));
+    };
+    let strips = offsets.zip(counts)
+        .map(|(offset, count)| tiff.get(offset as usize..(offset + count) as usize))
+        .collect::<Option<Vec<&[u8]>>>()
+        .ok_or_else(|| tiff_error("TIFF output strips are out of bounds".to_string()))?;
+
+    // Carried fields never override how the new pixels are laid out
+    let mut carried: Vec<Field> = metadata.exif.as_ref()
+        .and_then(|exif| exif::Reader::new().read_raw(exif.clone()).ok())
+        .map(|exif| exif.fields()
+            .filter(|f| f.ifd_num == In::PRIMARY && !replaced(f.tag) && !image_fields.iter().any(|i| i.tag == f.tag))
+            .cloned()
+            .collect())
+        .unwrap_or_default();
+    if let Some(dpi) = metadata.dpi {
+        carried.extend(resolution_fields(dpi));
+    }
+    if let Some(xmp) = &metadata.xmp {
+        carried.push(Field { tag: Tag(Context::Tiff, TIFF_XMP), ifd_num: In::PRIMARY, value: Value::Byte(xmp.clone()) });
+    }
+    if let Some(iptc) = &metadata.iptc {
+        carried.push(Field { tag: Tag(Context::Tiff, TIFF_IPTC), ifd_num: In::PRIMARY, value: Value::Undefined(iptc.clone(), 0) });
+    }
+    if let Some(icc) = &metadata.icc {
+        carried.push(Field { tag: Tag(Context::Tiff, TIFF_ICC), ifd_num: In::PRIMARY, value: Value::Undefined(icc.clone(), 0) });
+    }
+
+    let mut writer = exif::experimental::Writer::new();
+    for field in image_fields.into_iter().chain(&carried) {
+        if !matches!(field.value, Value::Unknown(..)) {
+            writer.push_field(field);
+        }
+    }
+    writer.set_strips(&strips, In::PRIMARY);
+
+    let mut output = Cursor::new(Vec::new());
+    writer.write(&mut output, image.little_endian())
+        .map_err(|e| tiff_error(format!("Failed to write TIFF metadata: {}", e)))?;
+    Ok(output.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ResizeConfig;
+    use crate::processing::encode_image;
+    use image::{DynamicImage, Rgb, RgbImage};
+
+    const XMP: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/" xmlns:exif="http://ns.adobe.com/exif/1.0/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmp:CreatorTool="Camera 1.0" exif:GPSLatitude="52,22.5N">
+   <exif:GPSLongitude>4,53.6E</exif:GPSLongitude>
+   <dc:creator><rdf:Seq><rdf:li>Jane Doe</rdf:li></rdf:Seq></dc:creator>
+   <dc:rights><rdf:Alt><rdf:li xml:lang="x-default">(c) Jane Doe</rdf:li></rdf:Alt></dc:rights>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+    fn ascii(tag: Tag, text: &str) -> Field {
+        Field { tag, ifd_num: In::PRIMARY, value: Value::Ascii(vec![text.as_bytes().to_vec()]) }
+    }
+
+    fn sample_metadata() -> Metadata {
+        let fields = [
+            ascii(Tag::Make, "Camera"),
+            ascii(Tag::Artist, "Jane Doe"),
+            ascii(Tag::Copyright, "(c) Jane Doe"),
+            Field { tag: Tag::Orientation, ifd_num: In::PRIMARY, value: Value::Short(vec![6]) },
+            ascii(Tag::GPSLatitudeRef, "N"),
+        ];
+
+        // By-line, Keywords, Copyright Notice
+        let mut iptc = Vec::new();
+        for (dataset, text) in [(80u8, "Jane Doe"), (25, "holiday"), (116, "(c) Jane Doe")] {
+            iptc.extend_from_slice(&[0x1C, 2, dataset]);
+            iptc.extend_from_slice(&(text.len() as u16).to_be_bytes());
+            iptc.extend_from_slice(text.as_bytes());
+        }
+
+        Metadata {
+            exif: write_exif(&fields, false),
+            xmp: Some(XMP.as_bytes().to_vec()),
+            iptc: Some(iptc),
+            icc: None,
+            dpi: None,
+        }
+    }
+
+    fn exif_fields(metadata: &Metadata) -> Vec<(Tag, Option<u32>)> {
+        let exif = exif::Reader::new().read_raw(metadata.exif.clone().unwrap()).unwrap();
+        exif.fields().map(|f| (f.tag, f.value.get_uint(0))).collect()
+    }
+
+    fn xmp_text(metadata: &Metadata) -> String {
+        String::from_utf8(metadata.xmp.clone().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_metadata_round_trips_through_outputs() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 12, Rgb([40, 90, 160])));
+        // Large enough to need two JPEG APP2 chunks
+        let icc = (0..70_000).map(|i| (i % 251) as u8).collect();
+        let metadata = Metadata { icc: Some(icc), ..sample_metadata() };
+
+        for format in [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP, ImageFormat::Tiff] {
+            let encoded = encode_image(&image, format, &ResizeConfig::new()).unwrap();
+            let output = metadata.embed(encoded, format).unwrap();
+
+            let decoded = image::load_from_memory(&output).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (16, 12), "{:?}", format);
+
+            let read = Metadata::read(&output, format);
+            assert!(exif_fields(&read).contains(&(Tag::Artist, None)), "{:?}", format);
+            assert_eq!(read.xmp, metadata.xmp, "{:?}", format);
+            assert_eq!(read.icc, metadata.icc, "{:?}", format);
+            if format == ImageFormat::WebP {
+                assert_eq!(read.iptc, None);
+            } else {
+                assert_eq!(read.iptc, metadata.iptc, "{:?}", format);
+            }
+            if format != ImageFormat::Tiff {
+                assert_eq!(read.exif, metadata.exif, "{:?}", format);
+            }
+        }
+    }
+
+    #[test]
+    fn test_density_round_trips() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 12, Rgb([40, 90, 160])));
+        let metadata = Metadata { dpi: Some(300.0), ..Metadata::default() };
+
+        for format in [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::Tiff] {
+            let encoded = encode_image(&image, format, &ResizeConfig::new()).unwrap();
+            assert_eq!(Metadata::read(&encoded, format).dpi, None, "{:?}", format);
+
+            let output = metadata.embed(encoded, format).unwrap();
+            assert!(image::load_from_memory(&output).is_ok(), "{:?}", format);
+            let dpi = Metadata::read(&output, format).dpi.unwrap();
+            assert!((dpi - 300.0).abs() < 0.1, "{:?} read {}", format, dpi);
+        }
+
+        // Carried EXIF resolution is brought in line with the new density
+        let fields = resolution_fields(72.0);
+        let exif = Metadata { exif: write_exif(&[ascii(Tag::Artist, "Jane Doe"), fields[0].clone()], false), ..Metadata::default() };
+        assert_eq!(exif.exif.as_deref().and_then(exif_dpi), Some(72.0));
+        let updated = exif.with_dpi(300.0);
+        assert_eq!(updated.exif.as_deref().and_then(exif_dpi), Some(300.0));
+        assert!(exif_fields(&updated).contains(&(Tag::Artist, None)));
+    }
+
+    #[test]
+    fn test_metadata_policies() {
+        assert!(sample_metadata().filter(MetadataPolicy::StripAll, false).is_empty());
+        assert_eq!(sample_metadata().filter(MetadataPolicy::KeepAll, false), sample_metadata());
+
+        let no_gps = sample_metadata().filter(MetadataPolicy::StripGps, false);
+        let fields = exif_fields(&no_gps);
+        assert!(fields.iter().all(|(tag, _)| tag.context() != Context::Gps));
+        assert!(fields.iter().any(|(tag, _)| *tag == Tag::Make));
+        let xmp = xmp_text(&no_gps);
+        assert!(!xmp.contains("GPS"));
+        assert!(xmp.contains("CreatorTool") && xmp.contains("Jane Doe"));
+        assert_eq!(no_gps.iptc, sample_metadata().iptc);
+
+        let rights = sample_metadata().filter(MetadataPolicy::CopyrightOnly, false);
+        let fields = exif_fields(&rights);
+        assert_eq!(fields.len(), 3);
+        assert!(fields.contains(&(Tag::Orientation, Some(6))));
+        let xmp = xmp_text(&rights);
+        assert!(!xmp.contains("GPS") && !xmp.contains("CreatorTool"));
+        assert!(xmp.contains("<dc:creator>") && xmp.contains("(c) Jane Doe"));
+        let iptc = rights.iptc.unwrap();
+        let datasets: Vec<u8> = iim_datasets(&iptc).iter().map(|d| d.1).collect();
+        assert_eq!(datasets, [80, 116]);
+    }
+
+    #[test]
+    fn test_orientation_reset() {
+        let metadata = sample_metadata().filter(MetadataPolicy::KeepAll, true);
+        let fields = exif_fields(&metadata);
+        assert!(fields.contains(&(Tag::Orientation, Some(1))));
+        assert!(fields.iter().any(|(tag, _)| *tag == Tag::GPSLatitudeRef));
+
+        // XMP without a tiff:Orientation property is left as it was
+        assert!(xmp_text(&metadata).contains("GPSLongitude"));
+    }
+
+    #[tokio::test]
+    async fn test_engine_applies_policy() {
+        use crate::processing::ProcessingEngine;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("photo.jpg");
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(40, 30, Rgb([200, 120, 60])));
+        let encoded = encode_image(&image, ImageFormat::Jpeg, &ResizeConfig::new()).unwrap();
+        std::fs::write(&input_path, sample_metadata().embed(encoded, ImageFormat::Jpeg).unwrap()).unwrap();
+
+        let engine = ProcessingEngine::new();
+        let output_path = temp_dir.path().join("stripped.jpg");
+        engine.process_file(&input_path, &output_path, &ResizeConfig::new().width(20)).await.unwrap();
+        let output = Metadata::read(&std::fs::read(&output_path).unwrap(), ImageFormat::Jpeg);
+        assert!(output.is_empty());
+
+        let output_path = temp_dir.path().join("published.png");
+        let config = ResizeConfig::new().width(20).metadata(MetadataPolicy::StripGps);
+        let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        // Turned upright, so the carried orientation is reset
+        assert_eq!((result.output_info.width, result.output_info.height), (20, 27));
+        let output = Metadata::read(&std::fs::read(&output_path).unwrap(), ImageFormat::Png);
+        let fields = exif_fields(&output);
+        assert!(fields.contains(&(Tag::Orientation, Some(1))));
+        assert!(fields.iter().all(|(tag, _)| tag.context() != Context::Gps));
+        assert!(output.iptc.is_some());
+    }
+}
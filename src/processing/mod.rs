@@ -3,21 +3,42 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use serde::Serialize;
 use tokio::fs;
 use tracing::debug;
 
-use crate::config::{ResizeConfig, ResizeMode, ImageFormat, ProcessingProfile};
+use crate::config::{CropStrategy, ResizeConfig, ResizeMode, ImageFormat, ProcessingProfile};
 use crate::error::{Result, FastResizeError, ErrorContext};
 
 pub mod resize;
 pub mod formats;
 pub mod memory;
 pub mod validation;
+pub mod benchmark;
+pub mod crop;
+pub mod animation;
+pub mod orientation;
+pub mod metadata;
+pub mod color;
+pub mod resample;
+pub mod target_size;
+pub mod similarity;
+pub mod sharpen;
 
 pub use resize::*;
 pub use formats::*;
 pub use memory::*;
 pub use validation::*;
+pub use benchmark::*;
+pub use crop::*;
+pub use animation::*;
+pub use orientation::*;
+pub use metadata::*;
+pub use color::*;
+pub use resample::*;
+pub use target_size::*;
+pub use similarity::*;
+pub use sharpen::*;
 
 /// Core processing engine for image operations
 pub struct ProcessingEngine {
@@ -51,14 +72,41 @@ impl ProcessingEngine {
         self.validator.validate_file(input_path).await
             .with_file_context(input_path.to_path_buf())?;
 
+        // Animated inputs keep every frame unless only a poster frame is wanted
+        if !config.poster {
+            if let Some(result) = self.process_animation(input_path, output_path, config, start_time).await? {
+                return Ok(result);
+            }
+        }
+
         // Load image
-        let (image, original_info) = self.load_image(input_path).await?;
+        let (image, original_info, metadata) = self.load_image(input_path, config).await?;
         
         // Resize image
-        let resized_image = self.resize_image(image, config, &original_info).await?;
+        let output_format = output_format(config, output_path);
+        let resized_image = self.resize_image(image, config, &original_info, output_format).await?;
+        
+        // Physical sizes tag the output with their resolution
+        let metadata = match config.mode.dpi() {
+            Some(dpi) => metadata.with_dpi(dpi),
+            None => metadata,
+        };
+        
+        // Convert colors after resizing, when there are fewest pixels
+        let (resized_image, metadata) = tokio::task::spawn_blocking({
+            let color_space = config.color_space;
+            move || {
+                let icc = metadata.icc.as_deref();
+                let conversion = ColorConversion::new(icc, color_space, embeds_metadata(output_format));
+                let image = conversion.apply(resized_image);
+                let icc = conversion.profile_for(&image);
+                (image, Metadata { icc, ..metadata })
+            }
+        }).await
+        .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))?;
         
         // Save image
-        let output_info = self.save_image(&resized_image, output_path, config).await?;
+        let (output_info, quality, similarity) = self.write_image(&resized_image, output_path, config, &metadata).await?;
         
         let processing_time = start_time.elapsed();
         
@@ -67,12 +115,118 @@ impl ProcessingEngine {
             output_path: output_path.to_path_buf(),
             original_info,
             output_info,
+            quality,
+            similarity,
             processing_time,
             success: true,
             error: None,
         })
     }
 
+    /// Resize every frame of an animated GIF or WebP into an animated output
+    ///
+    /// Returns `None` for still inputs and for output formats that cannot
+    /// hold an animation; those are processed as a single frame.
+    async fn process_animation(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        config: &ResizeConfig,
+        start_time: Instant,
+    ) -> Result<Option<ProcessingResult>> {
+        let input_format = detect_format_from_path(input_path)?;
+        let output_format = output_format(config, output_path);
+        if !is_animated_format(input_format) || !is_animated_format(output_format) {
+            return Ok(None);
+        }
+
+        let data = fs::read(input_path).await
+            .with_file_context(input_path.to_path_buf())?;
+        let file_size = data.len() as u64;
+        let mut metadata = Metadata::read(&data, input_format).filter(config.metadata, false);
+        let conversion = ColorConversion::new(
+            metadata.icc.as_deref(),
+            config.color_space,
+            embeds_metadata(output_format),
+        );
+
+        let animation = tokio::task::spawn_blocking(move || decode_animation(&data, input_format))
+            .await
+            .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))?
+            .with_file_context(input_path.to_path_buf())?;
+        let Some(animation) = animation else {
+            return Ok(None);
+        };
+
+        let (width, height) = animation.dimensions();
+        let original_info = ImageInfo {
+            path: input_path.to_path_buf(),
+            width,
+            height,
+            format: input_format,
+            file_size,
+            pixel_count: (width as u64) * (height as u64),
+            dpi: metadata.dpi,
+        };
+
+        // Resolve the crop once so every frame is cut the same way
+        let mut mode = resolve_mode(&config.mode, input_path, output_format).await?;
+        if let ResizeMode::Fill { width, height, crop, .. } = &mut mode {
+            if matches!(crop, CropStrategy::Attention) {
+                let first_frame = image::DynamicImage::ImageRgba8(animation.frames[0].image.clone());
+                let (x, y) = attention_focus(&first_frame, *width, *height);
+                *crop = CropStrategy::Focus { x, y };
+            }
+        }
+
+        let frame_count = animation.frames.len();
+        let (encoded, (output_width, output_height)) = tokio::task::spawn_blocking({
+            let resizer = ImageResizer::for_config(config);
+            let config = config.clone();
+            move || -> Result<(Vec<u8>, (u32, u32))> {
+                let animation = animation.map_frames(|frame| Ok(conversion.apply(resizer.resize(&frame, &mode)?)))?;
+                // Frames are RGBA, so the first one stands for all of them
+                let first_frame = image::DynamicImage::ImageRgba8(animation.frames[0].image.clone());
+                metadata.icc = conversion.profile_for(&first_frame);
+                let encoded = encode_animation(&animation, output_format, &config)?;
+                let encoded = metadata.embed(encoded, output_format)?;
+                Ok((encoded, animation.dimensions()))
+            }
+        }).await
+        .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))?
+        .with_file_context(output_path.to_path_buf())?;
+
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).await
+                .with_file_context(output_path.to_path_buf())?;
+        }
+        fs::write(output_path, &encoded).await
+            .with_file_context(output_path.to_path_buf())?;
+
+        debug!("Resized {} animation frames: {}x{} -> {}x{}",
+               frame_count, width, height, output_width, output_height);
+
+        Ok(Some(ProcessingResult {
+            input_path: input_path.to_path_buf(),
+            output_path: output_path.to_path_buf(),
+            original_info,
+            output_info: ImageInfo {
+                path: output_path.to_path_buf(),
+                width: output_width,
+                height: output_height,
+                format: output_format,
+                file_size: encoded.len() as u64,
+                pixel_count: (output_width as u64) * (output_height as u64),
+                dpi: None,
+            },
+            quality: has_quality(output_format, config).then_some(config.quality),
+            similarity: None,
+            processing_time: start_time.elapsed(),
+            success: true,
+            error: None,
+        }))
+    }
+
     /// Process a file using a processing profile
     pub async fn process_file_with_profile<P: AsRef<Path>>(
         &self,
@@ -100,17 +254,18 @@ impl ProcessingEngine {
         let output_path = output_dir.join(output_filename);
 
         // Create resize config from profile
-        let config = ResizeConfig {
-            mode: profile.resize_mode.clone(),
-            quality: profile.quality,
-            format: profile.format,
-        };
+        let config = ResizeConfig::from(profile);
 
         self.process_file(input_path, &output_path, &config).await
     }
 
-    /// Load an image from file
-    async fn load_image(&self, path: &Path) -> Result<(image::DynamicImage, ImageInfo)> {
+    /// Load an image from file, turned upright by its EXIF orientation when
+    /// `auto_orient` is set, along with the metadata its policy carries over
+    async fn load_image(
+        &self,
+        path: &Path,
+        config: &ResizeConfig,
+    ) -> Result<(image::DynamicImage, ImageInfo, Metadata)> {
         debug!("Loading image: {:?}", path);
 
         // Read file metadata
@@ -131,42 +286,61 @@ impl ProcessingEngine {
         // Determine if we should use memory mapping for large files
         let use_mmap = file_size > 100 * 1024 * 1024; // 100MB threshold
 
-        let image = if use_mmap {
-            self.load_image_mmap(path).await?
+        let format = detect_format_from_path(path)?;
+        let (image, orientation, metadata) = if use_mmap {
+            self.load_image_mmap(path, format).await?
+        } else {
+            self.load_image_standard(path, format).await?
+        };
+
+        // Orient before anything measures the image, so resize modes target
+        // the displayed axes
+        let oriented = config.auto_orient && orientation != Orientation::Normal;
+        let metadata = metadata.filter(config.metadata, oriented);
+        let image = if oriented {
+            debug!("Applying EXIF orientation {:?}", orientation);
+            tokio::task::spawn_blocking(move || orientation.apply(image)).await
+                .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))?
         } else {
-            self.load_image_standard(path).await?
+            image
         };
 
         let info = ImageInfo {
             path: path.to_path_buf(),
             width: image.width(),
             height: image.height(),
-            format: detect_format_from_path(path)?,
+            format,
             file_size,
             pixel_count: (image.width() as u64) * (image.height() as u64),
+            dpi: metadata.dpi,
         };
 
         debug!("Loaded image: {}x{} ({} pixels, {:.2}MB)", 
                info.width, info.height, info.pixel_count,
                info.file_size as f64 / 1024.0 / 1024.0);
 
-        Ok((image, info))
+        Ok((image, info, metadata))
     }
 
-    /// Load image using standard file I/O
-    async fn load_image_standard(&self, path: &Path) -> Result<image::DynamicImage> {
+    /// Load image, its EXIF orientation and metadata using standard file I/O
+    async fn load_image_standard(
+        &self,
+        path: &Path,
+        format: ImageFormat,
+    ) -> Result<(image::DynamicImage, Orientation, Metadata)> {
         let data = fs::read(path).await
             .with_file_context(path.to_path_buf())?;
 
         let image = tokio::task::spawn_blocking({
             let data = data.clone();
             let path = path.to_path_buf();
-            move || -> Result<image::DynamicImage> {
-                image::load_from_memory(&data)
+            move || -> Result<(image::DynamicImage, Orientation, Metadata)> {
+                let image = image::load_from_memory(&data)
                     .map_err(|e| FastResizeError::validation(
                         format!("Failed to decode image: {}", e),
                         Some(path),
-                    ))
+                    ))?;
+                Ok((image, Orientation::from_container(&data), Metadata::read(&data, format)))
             }
         }).await
         .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))??;
@@ -174,8 +348,12 @@ impl ProcessingEngine {
         Ok(image)
     }
 
-    /// Load image using memory mapping (for large files)
-    async fn load_image_mmap(&self, path: &Path) -> Result<image::DynamicImage> {
+    /// Load image, its EXIF orientation and metadata using memory mapping (for large files)
+    async fn load_image_mmap(
+        &self,
+        path: &Path,
+        format: ImageFormat,
+    ) -> Result<(image::DynamicImage, Orientation, Metadata)> {
         use memmap2::MmapOptions;
         use std::fs::File;
 
@@ -191,12 +369,13 @@ impl ProcessingEngine {
 
         let image = tokio::task::spawn_blocking({
             let path = path.to_path_buf();
-            move || -> Result<image::DynamicImage> {
-                image::load_from_memory(&mmap)
+            move || -> Result<(image::DynamicImage, Orientation, Metadata)> {
+                let image = image::load_from_memory(&mmap)
                     .map_err(|e| FastResizeError::validation(
                         format!("Failed to decode memory-mapped image: {}", e),
                         Some(path),
-                    ))
+                    ))?;
+                Ok((image, Orientation::from_container(&mmap), Metadata::read(&mmap, format)))
             }
         }).await
         .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))??;
@@ -209,55 +388,63 @@ impl ProcessingEngine {
         &self,
         image: image::DynamicImage,
         config: &ResizeConfig,
-        _original_info: &ImageInfo,
+        original_info: &ImageInfo,
+        output_format: ImageFormat,
     ) -> Result<image::DynamicImage> {
         debug!("Resizing image: {} -> {:?}", 
                format!("{}x{}", image.width(), image.height()),
                config.mode);
 
-        // Calculate target dimensions
-        let (target_width, target_height) = calculate_dimensions(
-            image.width(),
-            image.height(),
-            &config.mode,
-        )?;
+        let (original_width, original_height) = (image.width(), image.height());
 
-        debug!("Target dimensions: {}x{}", target_width, target_height);
-
-        // Check if resize is actually needed
-        if target_width == image.width() && target_height == image.height() {
-            debug!("No resize needed, dimensions already match target");
-            return Ok(image);
-        }
+        let mode = resolve_mode(&config.mode, &original_info.path, output_format).await?;
 
         // Perform the resize operation
         let resized = tokio::task::spawn_blocking({
-            let image = image.clone();
-            move || -> Result<image::DynamicImage> {
-                // Use high-quality filtering for better results
-                let filter = image::imageops::FilterType::Lanczos3;
-                
-                let resized = image.resize(target_width, target_height, filter);
-                
-                Ok(resized)
-            }
+            let resizer = ImageResizer::for_config(config);
+            move || resizer.resize(&image, &mode)
         }).await
         .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))??;
 
         debug!("Resize completed: {}x{} -> {}x{}", 
-               image.width(), image.height(),
+               original_width, original_height,
                resized.width(), resized.height());
 
         Ok(resized)
     }
 
     /// Save an image to file
-    async fn save_image(
+    pub async fn save_image(
+        &self,
+        image: &image::DynamicImage,
+        output_path: &Path,
+        config: &ResizeConfig,
+    ) -> Result<ImageInfo> {
+        self.save_image_with_metadata(image, output_path, config, &Metadata::default()).await
+    }
+
+    /// Save an image to file with metadata carried from its input
+    pub async fn save_image_with_metadata(
         &self,
         image: &image::DynamicImage,
         output_path: &Path,
         config: &ResizeConfig,
+        metadata: &Metadata,
     ) -> Result<ImageInfo> {
+        let (info, _, _) = self.write_image(image, output_path, config, metadata).await?;
+        Ok(info)
+    }
+
+    /// Encode and write an image, within the target size if one is set,
+    /// returning the written file's info, the quality it was encoded at and
+    /// the similarity score it reached
+    async fn write_image(
+        &self,
+        image: &image::DynamicImage,
+        output_path: &Path,
+        config: &ResizeConfig,
+        metadata: &Metadata,
+    ) -> Result<(ImageInfo, Option<u8>, Option<f64>)> {
         debug!("Saving image: {:?}", output_path);
 
         // Ensure output directory exists
@@ -266,128 +453,39 @@ impl ProcessingEngine {
                 .with_file_context(output_path.to_path_buf())?;
         }
 
-        // Determine output format
-        let output_format = config.format
-            .or_else(|| detect_format_from_path(output_path).ok())
-            .unwrap_or(ImageFormat::Jpeg);
+        let output_format = output_format(config, output_path);
 
         debug!("Output format: {:?}, quality: {}", output_format, config.quality);
 
-        // Save the image
-        let file_size = tokio::task::spawn_blocking({
+        // Encode off the async runtime, then write the result
+        let encoded = tokio::task::spawn_blocking({
             let image = image.clone();
-            let output_path = output_path.to_path_buf();
-            let quality = config.quality;
-            move || -> Result<u64> {
-                // Convert quality to appropriate format
-                match output_format {
-                    ImageFormat::Jpeg => {
-                        let mut output = std::fs::File::create(&output_path)
-                            .with_file_context(output_path.clone())?;
-                        
-                        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
-                            &mut output, quality
-                        );
-                        
-                        image.write_with_encoder(encoder)
-                            .with_file_context(output_path.clone())?;
-                    }
-                    ImageFormat::Png => {
-                        // PNG uses compression level instead of quality
-                        // Only apply custom compression if quality is explicitly set (not default 90)
-                        if quality != 90 {
-                            // User explicitly set quality, apply compression settings
-                            let mut output = std::fs::File::create(&output_path)
-                                .with_file_context(output_path.clone())?;
-                            
-                            // Map quality to compression type for speed/size tradeoff
-                            // Lower quality = faster compression, larger files
-                            // Higher quality = slower compression, smaller files
-                            let (compression, filter) = if quality < 50 {
-                                // Speed priority: fast compression, simple filter
-                                (image::codecs::png::CompressionType::Fast, 
-                                 image::codecs::png::FilterType::NoFilter)
-                            } else if quality < 80 {
-                                // Balanced: fast compression, sub filter
-                                (image::codecs::png::CompressionType::Fast,
-                                 image::codecs::png::FilterType::Sub)
-                            } else if quality < 95 {
-                                // Default balance
-                                (image::codecs::png::CompressionType::Default,
-                                 image::codecs::png::FilterType::Sub)
-                            } else {
-                                // Size priority: best compression, adaptive filter (slower)
-                                (image::codecs::png::CompressionType::Best,
-                                 image::codecs::png::FilterType::Adaptive)
-                            };
-                            
-                            let encoder = image::codecs::png::PngEncoder::new_with_quality(
-                                &mut output,
-                                compression,
-                                filter
-                            );
-                            
-                            image.write_with_encoder(encoder)
-                                .with_file_context(output_path.clone())?;
-                        } else {
-                            // Default quality - use fast standard save for best speed
-                            image.save(&output_path)
-                                .with_file_context(output_path.clone())?;
-                        }
-                    }
-                    ImageFormat::WebP => {
-                        // WebP supports quality directly but needs the webp feature
-                        // For now, we'll use the standard save with a note
-                        // TODO: Add webp-encoder crate for quality control
-                        
-                        // Note: WebP quality control requires additional dependencies
-                        // Using lossy encoding when available
-                        if quality < 100 {
-                            // For JPEG-like quality in WebP, convert to RGB8 first
-                            let rgb_image = image.to_rgb8();
-                            rgb_image.save(&output_path)
-                                .with_file_context(output_path.clone())?;
-                        } else {
-                            image.save(&output_path)
-                                .with_file_context(output_path.clone())?;
-                        }
-                    }
-                    _ => {
-                        // For other formats, try to apply quality if it's JPEG-like
-                        if quality < 100 && (output_format == ImageFormat::Bmp || output_format == ImageFormat::Tiff) {
-                            // These formats don't support quality, just save as-is
-                            image.save(&output_path)
-                                .with_file_context(output_path.clone())?;
-                        } else {
-                            image.save(&output_path)
-                                .with_file_context(output_path.clone())?;
-                        }
-                    }
-                }
-
-                // Get file size
-                let metadata = std::fs::metadata(&output_path)
-                    .with_file_context(output_path.clone())?;
-                
-                Ok(metadata.len())
-            }
+            let config = config.clone();
+            let metadata = metadata.clone();
+            move || encode_output(&image, output_format, &config, &metadata)
         }).await
-        .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))??;
+        .map_err(|e| FastResizeError::system(format!("Task join error: {}", e)))?
+        .with_file_context(output_path.to_path_buf())?;
+
+        fs::write(output_path, &encoded.data).await
+            .with_file_context(output_path.to_path_buf())?;
+        let file_size = encoded.data.len() as u64;
 
         let info = ImageInfo {
             path: output_path.to_path_buf(),
-            width: image.width(),
-            height: image.height(),
+            width: encoded.width,
+            height: encoded.height,
             format: output_format,
             file_size,
-            pixel_count: (image.width() as u64) * (image.height() as u64),
+            pixel_count: (encoded.width as u64) * (encoded.height as u64),
+            dpi: metadata.dpi.filter(|_| embeds_density(output_format)),
         };
 
         debug!("Saved image: {}x{} ({:.2}MB)", 
                info.width, info.height,
                info.file_size as f64 / 1024.0 / 1024.0);
 
-        Ok(info)
+        Ok((info, encoded.quality, encoded.similarity))
     }
 }
 
@@ -397,8 +495,33 @@ impl Default for ProcessingEngine {
     }
 }
 
+/// Output format from the configuration, else the output extension, else JPEG
+fn output_format(config: &ResizeConfig, output_path: &Path) -> ImageFormat {
+    config.format
+        .or_else(|| detect_format_from_path(output_path).ok())
+        .unwrap_or(ImageFormat::Jpeg)
+}
+
+/// Resolve the parts of a resize mode that depend on the file: a sidecar
+/// crop strategy becomes the focus point from the input's sidecar, and
+/// transparent padding becomes white for formats without alpha
+async fn resolve_mode(mode: &ResizeMode, input_path: &Path, output_format: ImageFormat) -> Result<ResizeMode> {
+    let mut mode = mode.clone();
+    match &mut mode {
+        ResizeMode::Fill { crop: crop @ CropStrategy::Sidecar, .. } => {
+            match read_sidecar_focus(input_path).await? {
+                Some((x, y)) => *crop = CropStrategy::Focus { x, y },
+                None => debug!("No focus sidecar for {:?}, using crop anchor", input_path),
+            }
+        }
+        ResizeMode::Pad { background, .. } => *background = background.for_format(output_format),
+        _ => {}
+    }
+    Ok(mode)
+}
+
 /// Information about an image file
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ImageInfo {
     pub path: PathBuf,
     pub width: u32,
@@ -406,15 +529,24 @@ pub struct ImageInfo {
     pub format: ImageFormat,
     pub file_size: u64,
     pub pixel_count: u64,
+    /// Pixel density in dots per inch, when the file records one
+    pub dpi: Option<f32>,
 }
 
 /// Result of processing an image
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProcessingResult {
     pub input_path: PathBuf,
     pub output_path: PathBuf,
     pub original_info: ImageInfo,
     pub output_info: ImageInfo,
+    /// Quality the output was encoded at, for JPEG, lossy WebP and AVIF;
+    /// below the configured quality when lowered to meet a target size
+    pub quality: Option<u8>,
+    /// SSIM or MS-SSIM of the output against the resized image, when a
+    /// minimum similarity was set
+    pub similarity: Option<f64>,
+    #[serde(rename = "processing_time_secs", serialize_with = "serialize_duration_secs")]
     pub processing_time: Duration,
     pub success: bool,
     pub error: Option<String>,
@@ -437,6 +569,7 @@ impl ProcessingResult {
                 format: ImageFormat::Jpeg,
                 file_size: 0,
                 pixel_count: 0,
+                dpi: None,
             },
             output_info: ImageInfo {
                 path: PathBuf::new(),
@@ -445,7 +578,10 @@ impl ProcessingResult {
                 format: ImageFormat::Jpeg,
                 file_size: 0,
                 pixel_count: 0,
+                dpi: None,
             },
+            quality: None,
+            similarity: None,
             processing_time,
             success: false,
             error: Some(error.user_message()),
@@ -478,6 +614,25 @@ impl ProcessingResult {
     }
 }
 
+/// Serialize a duration as fractional seconds
+pub(crate) fn serialize_duration_secs<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Serialize an optional duration as fractional seconds
+pub(crate) fn serialize_optional_duration_secs<S: serde::Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+        None => serializer.serialize_none(),
+    }
+}
+
 /// Calculate target dimensions based on resize mode
 pub fn calculate_dimensions(
     original_width: u32,
@@ -497,18 +652,79 @@ pub fn calculate_dimensions(
         }
         
         ResizeMode::Width { width } => {
+            if *width == 0 {
+                return Err(FastResizeError::invalid_parameters(
+                    "Width must be greater than 0"
+                ));
+            }
             let aspect_ratio = original_height as f32 / original_width as f32;
             let height = (*width as f32 * aspect_ratio).round() as u32;
             Ok((*width, height.max(1)))
         }
         
         ResizeMode::Height { height } => {
+            if *height == 0 {
+                return Err(FastResizeError::invalid_parameters(
+                    "Height must be greater than 0"
+                ));
+            }
             let aspect_ratio = original_width as f32 / original_height as f32;
             let width = (*height as f32 * aspect_ratio).round() as u32;
             Ok((width.max(1), *height))
         }
         
+        ResizeMode::LongEdge { px } | ResizeMode::ShortEdge { px } => {
+            if *px == 0 {
+                return Err(FastResizeError::invalid_parameters(
+                    "Edge length must be greater than 0"
+                ));
+            }
+            let landscape = original_width >= original_height;
+            let edge = if matches!(mode, ResizeMode::LongEdge { .. }) == landscape {
+                ResizeMode::Width { width: *px }
+            } else {
+                ResizeMode::Height { height: *px }
+            };
+            calculate_dimensions(original_width, original_height, &edge)
+        }
+        
+        ResizeMode::MaxPixels { megapixels } => {
+            if *megapixels <= 0.0 {
+                return Err(FastResizeError::invalid_parameters(
+                    "Pixel budget must be positive"
+                ));
+            }
+            let budget = f64::from(*megapixels) * 1_000_000.0;
+            let pixels = f64::from(original_width) * f64::from(original_height);
+            if pixels <= budget {
+                return Ok((original_width, original_height));
+            }
+            // Round down so the area stays within the budget
+            let factor = (budget / pixels).sqrt();
+            let width = (f64::from(original_width) * factor).floor() as u32;
+            let height = (f64::from(original_height) * factor).floor() as u32;
+            Ok((width.max(1), height.max(1)))
+        }
+        
+        ResizeMode::Physical { width, height, unit, dpi } => {
+            if !dpi.is_finite() || *dpi <= 0.0 {
+                return Err(FastResizeError::invalid_parameters(
+                    "Resolution must be positive"
+                ));
+            }
+            let fit = ResizeMode::Fit {
+                width: unit.to_pixels(*width, *dpi),
+                height: unit.to_pixels(*height, *dpi),
+            };
+            calculate_dimensions(original_width, original_height, &fit)
+        }
+        
         ResizeMode::Fit { width, height } => {
+            if *width == 0 || *height == 0 {
+                return Err(FastResizeError::invalid_parameters(
+                    "Width and height must be greater than 0"
+                ));
+            }
             let original_aspect = original_width as f32 / original_height as f32;
             let target_aspect = *width as f32 / *height as f32;
             
@@ -523,10 +739,14 @@ pub fn calculate_dimensions(
             }
         }
         
-        ResizeMode::Fill { width, height } => {
-            // For fill mode, we resize to fill the target dimensions
-            // This may require cropping, which isn't implemented yet
-            // For now, we just resize to fill (may change aspect ratio)
+        ResizeMode::Fill { width, height, .. } | ResizeMode::Pad { width, height, .. } => {
+            if *width == 0 || *height == 0 {
+                return Err(FastResizeError::invalid_parameters(
+                    "Width and height must be greater than 0"
+                ));
+            }
+            // Output is exactly the target size; the resizer crops the
+            // overflow or pads the rest
             Ok((*width, *height))
         }
     }
@@ -535,6 +755,154 @@ pub fn calculate_dimensions(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{Background, CropAnchor, LengthUnit};
+
+    #[tokio::test]
+    async fn test_fill_produces_exact_size() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("input.png");
+        let output_path = temp_dir.path().join("output.png");
+        image::RgbImage::from_pixel(300, 200, image::Rgb([10, 20, 30]))
+            .save(&input_path)
+            .unwrap();
+
+        let config = ResizeConfig::new().fill_anchored(120, 90, CropAnchor::TopLeft);
+        let result = ProcessingEngine::new()
+            .process_file(&input_path, &output_path, &config)
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let output = image::open(&output_path).unwrap();
+        assert_eq!((output.width(), output.height()), (120, 90));
+    }
+
+    #[tokio::test]
+    async fn test_fill_uses_focus_sidecar() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("input.png");
+        let output_path = temp_dir.path().join("output.png");
+
+        // Left half red, right half blue
+        image::RgbImage::from_fn(200, 100, |x, _| {
+            if x < 100 { image::Rgb([255, 0, 0]) } else { image::Rgb([0, 0, 255]) }
+        })
+        .save(&input_path)
+        .unwrap();
+        std::fs::write(temp_dir.path().join("input.json"), r#"{"focus": {"x": 0.9, "y": 0.5}}"#).unwrap();
+
+        let config = ResizeConfig::new()
+            .mode(ResizeMode::Fill { width: 40, height: 40, anchor: CropAnchor::Center, crop: CropStrategy::Sidecar })
+            .filter(crate::config::FilterType::Nearest);
+        let result = ProcessingEngine::new()
+            .process_file(&input_path, &output_path, &config)
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let output = image::open(&output_path).unwrap().to_rgb8();
+        assert!(output.pixels().all(|p| p.0 == [0, 0, 255]));
+    }
+
+    #[tokio::test]
+    async fn test_pad_background_follows_output_format() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("logo.png");
+        image::RgbaImage::from_pixel(200, 100, image::Rgba([200, 0, 0, 255]))
+            .save(&input_path)
+            .unwrap();
+
+        let engine = ProcessingEngine::new();
+        let config = ResizeConfig::new().mode(ResizeMode::pad(80, 80, Background::Transparent));
+
+        let output_path = temp_dir.path().join("logo_padded.png");
+        let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        assert_eq!((result.output_info.width, result.output_info.height), (80, 80));
+        let output = image::open(&output_path).unwrap().to_rgba8();
+        assert_eq!(output.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(output.get_pixel(40, 40).0, [200, 0, 0, 255]);
+
+        // JPEG has no alpha channel, so the padding turns white
+        let output_path = temp_dir.path().join("logo_padded.jpg");
+        engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        let output = image::open(&output_path).unwrap().to_rgb8();
+        assert_eq!((output.width(), output.height()), (80, 80));
+        assert!(output.get_pixel(0, 0).0.iter().all(|&c| c > 245));
+    }
+
+    #[tokio::test]
+    async fn test_target_size_records_quality() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("photo.png");
+        image::RgbImage::from_fn(300, 200, |x, y| {
+            let n = x.wrapping_mul(7919) ^ y.wrapping_mul(104_729);
+            image::Rgb([(n % 251) as u8, (n / 5 % 241) as u8, (x + y) as u8])
+        })
+        .save(&input_path)
+        .unwrap();
+
+        let engine = ProcessingEngine::new();
+        let output_path = temp_dir.path().join("photo.jpg");
+        let config = ResizeConfig::new().quality(90);
+        let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        assert_eq!(result.quality, Some(90));
+
+        let budget = result.output_info.file_size / 2;
+        let result = engine.process_file(&input_path, &output_path, &config.target_size(budget)).await.unwrap();
+        assert!(result.output_info.file_size <= budget);
+        assert_eq!(std::fs::metadata(&output_path).unwrap().len(), result.output_info.file_size);
+        assert!(result.quality.unwrap() < 90);
+
+        // Lossless outputs have no quality to report
+        let output_path = temp_dir.path().join("photo_copy.png");
+        let result = engine.process_file(&input_path, &output_path, &ResizeConfig::new()).await.unwrap();
+        assert_eq!(result.quality, None);
+        assert_eq!(result.similarity, None);
+
+        // A similarity target picks the quality and reports the score reached
+        let output_path = temp_dir.path().join("photo_ssim.jpg");
+        let config = ResizeConfig::new().width(150).min_similarity(crate::config::SimilarityTarget::Ssim(0.9));
+        let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        assert!(result.similarity.unwrap() >= 0.9);
+        assert!(result.quality.unwrap() < 90);
+    }
+
+    #[tokio::test]
+    async fn test_animated_gif_keeps_frames() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("sticker.gif");
+
+        let frames = (0..4u8)
+            .map(|index| AnimationFrame {
+                image: image::RgbaImage::from_pixel(120, 80, image::Rgba([index * 60, 0, 255 - index * 60, 255])),
+                delay_ms: 80,
+                disposal: FrameDisposal::Keep,
+            })
+            .collect();
+        let animation = Animation { frames, loop_count: 0 };
+        std::fs::write(&input_path, encode_animation(&animation, ImageFormat::Gif, &ResizeConfig::new()).unwrap()).unwrap();
+
+        // GIF to animated WebP
+        let engine = ProcessingEngine::new();
+        let output_path = temp_dir.path().join("sticker.webp");
+        let config = ResizeConfig::new().width(60).format(ImageFormat::WebP);
+        let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        assert_eq!((result.output_info.width, result.output_info.height), (60, 40));
+
+        let output = decode_animation(&std::fs::read(&output_path).unwrap(), ImageFormat::WebP).unwrap().unwrap();
+        assert_eq!(output.frames.len(), 4);
+        assert_eq!(output.dimensions(), (60, 40));
+        assert!(output.frames.iter().all(|frame| frame.delay_ms == 80));
+        assert_eq!(output.loop_count, 0);
+
+        // Poster keeps the first frame only
+        let poster_path = temp_dir.path().join("poster.gif");
+        let config = ResizeConfig::new().width(60).poster(true);
+        engine.process_file(&input_path, &poster_path, &config).await.unwrap();
+        let poster = std::fs::read(&poster_path).unwrap();
+        assert!(decode_animation(&poster, ImageFormat::Gif).unwrap().is_none());
+        assert_eq!(image::load_from_memory(&poster).unwrap().width(), 60);
+    }
 
     #[test]
     fn test_calculate_dimensions_scale() {
@@ -565,6 +933,73 @@ mod tests {
         assert_eq!(result.unwrap(), (480, 600));
     }
 
+    #[test]
+    fn test_calculate_dimensions_edges() {
+        // Landscape and portrait images get the same long and short edges
+        let long_edge = ResizeMode::LongEdge { px: 500 };
+        assert_eq!(calculate_dimensions(1000, 800, &long_edge).unwrap(), (500, 400));
+        assert_eq!(calculate_dimensions(800, 1000, &long_edge).unwrap(), (400, 500));
+
+        let short_edge = ResizeMode::ShortEdge { px: 400 };
+        assert_eq!(calculate_dimensions(1000, 800, &short_edge).unwrap(), (500, 400));
+        assert_eq!(calculate_dimensions(800, 1000, &short_edge).unwrap(), (400, 500));
+
+        assert!(calculate_dimensions(1000, 800, &ResizeMode::LongEdge { px: 0 }).is_err());
+    }
+
+    #[test]
+    fn test_calculate_dimensions_max_pixels() {
+        let (width, height) = calculate_dimensions(4000, 3000, &ResizeMode::MaxPixels { megapixels: 2.0 }).unwrap();
+        assert!(width * height <= 2_000_000);
+        assert_eq!((width, height), (1632, 1224));
+
+        // Images within the budget keep their size
+        let result = calculate_dimensions(1000, 800, &ResizeMode::MaxPixels { megapixels: 2.0 });
+        assert_eq!(result.unwrap(), (1000, 800));
+        assert!(calculate_dimensions(1000, 800, &ResizeMode::MaxPixels { megapixels: 0.0 }).is_err());
+    }
+
+    #[test]
+    fn test_calculate_dimensions_physical() {
+        // 6x4 inches at 300 DPI fits within 1800x1200
+        let mode = ResizeMode::Physical { width: 6.0, height: 4.0, unit: LengthUnit::Inch, dpi: 300.0 };
+        assert_eq!(calculate_dimensions(3000, 2000, &mode).unwrap(), (1800, 1200));
+        assert_eq!(calculate_dimensions(2000, 2000, &mode).unwrap(), (1200, 1200));
+
+        let mode = ResizeMode::Physical { width: 254.0, height: 254.0, unit: LengthUnit::Millimeter, dpi: 100.0 };
+        assert_eq!(calculate_dimensions(500, 2000, &mode).unwrap(), (250, 1000));
+
+        let mode = ResizeMode::Physical { width: 6.0, height: 4.0, unit: LengthUnit::Inch, dpi: 0.0 };
+        assert!(calculate_dimensions(3000, 2000, &mode).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_physical_size_tags_density() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("photo.png");
+        image::RgbImage::from_pixel(600, 400, image::Rgb([80, 120, 160])).save(&input_path).unwrap();
+
+        let engine = ProcessingEngine::new();
+        let config = ResizeConfig::new().physical(2.0, 2.0, LengthUnit::Inch, 150.0);
+        for (name, format) in [("print.jpg", ImageFormat::Jpeg), ("print.png", ImageFormat::Png), ("print.tif", ImageFormat::Tiff)] {
+            let output_path = temp_dir.path().join(name);
+            let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+            assert_eq!(result.original_info.dpi, None);
+            assert_eq!((result.output_info.width, result.output_info.height), (300, 200));
+            assert_eq!(result.output_info.dpi, Some(150.0), "{:?}", format);
+
+            // Read back as an input, the density is reported
+            let copy_path = temp_dir.path().join(format!("copy_{}", name));
+            let result = engine.process_file(&output_path, &copy_path, &ResizeConfig::new()).await.unwrap();
+            assert_eq!(result.original_info.dpi, Some(150.0), "{:?}", format);
+        }
+
+        // Formats without a density field do not report one
+        let output_path = temp_dir.path().join("print.webp");
+        let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        assert_eq!(result.output_info.dpi, None);
+    }
+
     #[test]
     fn test_processing_result_metrics() {
         let result = ProcessingResult {
@@ -577,6 +1012,7 @@ mod tests {
                 format: ImageFormat::Jpeg,
                 file_size: 1000000, // 1MB
                 pixel_count: 800000,
+                dpi: None,
             },
             output_info: ImageInfo {
                 path: PathBuf::from("output.jpg"),
@@ -585,7 +1021,10 @@ mod tests {
                 format: ImageFormat::Jpeg,
                 file_size: 250000, // 250KB
                 pixel_count: 200000,
+                dpi: None,
             },
+            quality: Some(85),
+            similarity: None,
             processing_time: Duration::from_secs(1),
             success: true,
             error: None,
@@ -595,4 +1034,35 @@ mod tests {
         assert!((result.size_reduction() - 75.0).abs() < 0.1);
         assert!((result.pixels_per_second() - 800000.0).abs() < 1.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_processing_result_serialization() {
+        let info = ImageInfo {
+            path: PathBuf::from("input.jpg"),
+            width: 100,
+            height: 50,
+            format: ImageFormat::Jpeg,
+            file_size: 1234,
+            pixel_count: 5000,
+            dpi: None,
+        };
+        let result = ProcessingResult {
+            input_path: PathBuf::from("input.jpg"),
+            output_path: PathBuf::from("output.webp"),
+            original_info: info.clone(),
+            output_info: ImageInfo { format: ImageFormat::WebP, ..info },
+            quality: Some(80),
+            similarity: Some(0.985),
+            processing_time: Duration::from_millis(1500),
+            success: true,
+            error: None,
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["output_path"], "output.webp");
+        assert_eq!(json["output_info"]["format"], "webp");
+        assert_eq!(json["quality"], 80);
+        assert_eq!(json["similarity"], 0.985);
+        assert_eq!(json["processing_time_secs"], 1.5);
+    }
+}
@@ -0,0 +1,182 @@
+//! EXIF orientation handling
+
+use std::io::Cursor;
+
+use image::DynamicImage;
+use serde::Serialize;
+
+/// EXIF orientation (tag 0x0112): how stored pixels must be transformed to
+/// display upright
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    /// 1: stored upright
+    #[default]
+    Normal,
+    /// 2: mirrored left to right
+    FlipHorizontal,
+    /// 3: upside down
+    Rotate180,
+    /// 4: mirrored top to bottom
+    FlipVertical,
+    /// 5: mirrored along the top-left to bottom-right diagonal
+    Transpose,
+    /// 6: needs a 90° clockwise turn
+    Rotate90,
+    /// 7: mirrored along the top-right to bottom-left diagonal
+    Transverse,
+    /// 8: needs a 90° counter-clockwise turn
+    Rotate270,
+}
+
+impl Orientation {
+    /// Map an EXIF orientation value (1-8)
+    pub fn from_exif(value: u32) -> Option<Self> {
+        match value {
+            1 => Some(Self::Normal),
+            2 => Some(Self::FlipHorizontal),
+            3 => Some(Self::Rotate180),
+            4 => Some(Self::FlipVertical),
+            5 => Some(Self::Transpose),
+            6 => Some(Self::Rotate90),
+            7 => Some(Self::Transverse),
+            8 => Some(Self::Rotate270),
+            _ => None,
+        }
+    }
+
+    /// Read the orientation from an encoded JPEG, PNG, WebP or TIFF file
+    ///
+    /// Missing, unreadable or out-of-range tags count as `Normal`.
+    pub fn from_container(data: &[u8]) -> Self {
+        exif::Reader::new()
+            .read_from_container(&mut Cursor::new(data))
+            .ok()
+            .and_then(|exif| {
+                exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                    .and_then(|field| field.value.get_uint(0))
+            })
+            .and_then(Self::from_exif)
+            .unwrap_or_default()
+    }
+
+    /// Whether displaying upright swaps width and height
+    pub fn swaps_dimensions(self) -> bool {
+        matches!(self, Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270)
+    }
+
+    /// Transform stored pixels so the image displays upright
+    pub fn apply(self, image: DynamicImage) -> DynamicImage {
+        match self {
+            Self::Normal => image,
+            Self::FlipHorizontal => image.fliph(),
+            Self::Rotate180 => image.rotate180(),
+            Self::FlipVertical => image.flipv(),
+            Self::Transpose => image.rotate90().fliph(),
+            Self::Rotate90 => image.rotate90(),
+            Self::Transverse => image.rotate270().fliph(),
+            Self::Rotate270 => image.rotate270(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    /// JPEG with an EXIF APP1 segment holding only the orientation tag
+    fn jpeg_with_orientation(image: &DynamicImage, orientation: u16) -> Vec<u8> {
+        let mut jpeg = Vec::new();
+        image.write_to(&mut Cursor::new(&mut jpeg), image::ImageFormat::Jpeg).unwrap();
+
+        // Little-endian TIFF header, one IFD entry: Orientation, SHORT, count 1
+        let mut tiff = b"II\x2a\x00\x08\x00\x00\x00\x01\x00\x12\x01\x03\x00\x01\x00\x00\x00".to_vec();
+        tiff.extend_from_slice(&orientation.to_le_bytes());
+        tiff.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+        let mut app1 = b"Exif\x00\x00".to_vec();
+        app1.extend_from_slice(&tiff);
+        let length = (app1.len() + 2) as u16;
+
+        let mut output = jpeg[..2].to_vec();
+        output.extend_from_slice(&[0xFF, 0xE1]);
+        output.extend_from_slice(&length.to_be_bytes());
+        output.extend_from_slice(&app1);
+        output.extend_from_slice(&jpeg[2..]);
+        output
+    }
+
+    /// 3x2 image whose top-left pixel is red and top-right pixel is green
+    fn marked_image() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(3, 2, |x, y| match (x, y) {
+            (0, 0) => Rgb([255, 0, 0]),
+            (2, 0) => Rgb([0, 255, 0]),
+            _ => Rgb([0, 0, 0]),
+        }))
+    }
+
+    #[test]
+    fn test_read_orientation() {
+        let image = marked_image();
+        assert_eq!(Orientation::from_container(&jpeg_with_orientation(&image, 6)), Orientation::Rotate90);
+        assert_eq!(Orientation::from_container(&jpeg_with_orientation(&image, 9)), Orientation::Normal);
+
+        let mut plain = Vec::new();
+        image.write_to(&mut Cursor::new(&mut plain), image::ImageFormat::Png).unwrap();
+        assert_eq!(Orientation::from_container(&plain), Orientation::Normal);
+    }
+
+    #[test]
+    fn test_apply_orientation() {
+        // Where the stored top-left (red) and top-right (green) pixels end up
+        let cases = [
+            (1, (3, 2), (0, 0), (2, 0)),
+            (2, (3, 2), (2, 0), (0, 0)),
+            (3, (3, 2), (2, 1), (0, 1)),
+            (4, (3, 2), (0, 1), (2, 1)),
+            (5, (2, 3), (0, 0), (0, 2)),
+            (6, (2, 3), (1, 0), (1, 2)),
+            (7, (2, 3), (1, 2), (1, 0)),
+            (8, (2, 3), (0, 2), (0, 0)),
+        ];
+
+        for (value, size, red, green) in cases {
+            let orientation = Orientation::from_exif(value).unwrap();
+            let oriented = orientation.apply(marked_image()).to_rgb8();
+            assert_eq!(oriented.dimensions(), size, "orientation {}", value);
+            assert_eq!(orientation.swaps_dimensions(), size.0 == 2);
+            assert_eq!(oriented.get_pixel(red.0, red.1).0, [255, 0, 0], "orientation {}", value);
+            assert_eq!(oriented.get_pixel(green.0, green.1).0, [0, 255, 0], "orientation {}", value);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_engine_orients_before_resizing() {
+        use crate::config::ResizeConfig;
+        use crate::processing::ProcessingEngine;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input_path = temp_dir.path().join("phone.jpg");
+        let stored = DynamicImage::ImageRgb8(RgbImage::from_pixel(60, 40, Rgb([90, 120, 150])));
+        std::fs::write(&input_path, jpeg_with_orientation(&stored, 6)).unwrap();
+
+        let engine = ProcessingEngine::new();
+        let output_path = temp_dir.path().join("upright.jpg");
+        let result = engine
+            .process_file(&input_path, &output_path, &ResizeConfig::new().width(20))
+            .await
+            .unwrap();
+        assert_eq!((result.original_info.width, result.original_info.height), (40, 60));
+        assert_eq!((result.output_info.width, result.output_info.height), (20, 30));
+
+        // The output is already upright and carries no orientation tag
+        let output = std::fs::read(&output_path).unwrap();
+        assert_eq!(Orientation::from_container(&output), Orientation::Normal);
+
+        let output_path = temp_dir.path().join("stored.jpg");
+        let config = ResizeConfig::new().width(20).auto_orient(false);
+        let result = engine.process_file(&input_path, &output_path, &config).await.unwrap();
+        assert_eq!((result.output_info.width, result.output_info.height), (20, 13));
+    }
+}
@@ -0,0 +1,156 @@
+//! Resampling in linear light and with premultiplied alpha
+//!
+//! Averaging gamma-encoded sRGB values darkens high-contrast detail, since
+//! the mean of black and white codes is much darker than the mean of their
+//! light; averaging straight alpha pulls the color of invisible pixels into
+//! visible edges. The resampler can decode to linear light and always
+//! weights color by alpha before filtering, then undoes both.
+
+use image::{ColorType, DynamicImage, Rgba32FImage};
+
+/// Decode an sRGB-encoded value (0.0-1.0) to linear light
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear-light value (0.0-1.0) as sRGB
+pub fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Resize to exactly `width`x`height`, in linear light if requested
+///
+/// Images with an alpha channel are filtered with premultiplied alpha. The
+/// result keeps the color type of the input.
+pub fn resample(
+    image: &DynamicImage,
+    width: u32,
+    height: u32,
+    filter: image::imageops::FilterType,
+    linear_light: bool,
+) -> DynamicImage {
+    let has_alpha = image.color().has_alpha();
+    if !linear_light && !has_alpha {
+        return image.resize_exact(width, height, filter);
+    }
+
+    let mut pixels = if linear_light { to_linear(image) } else { image.to_rgba32f() };
+    if has_alpha {
+        for pixel in pixels.pixels_mut() {
+            let alpha = pixel.0[3];
+            for channel in &mut pixel.0[..3] {
+                *channel *= alpha;
+            }
+        }
+    }
+
+    let mut resized = image::imageops::resize(&pixels, width, height, filter);
+
+    for pixel in resized.pixels_mut() {
+        let alpha = pixel.0[3].clamp(0.0, 1.0);
+        pixel.0[3] = alpha;
+        if has_alpha {
+            // Fully transparent pixels have no color left to recover
+            let scale = if alpha > 0.0 { 1.0 / alpha } else { 0.0 };
+            for channel in &mut pixel.0[..3] {
+                *channel = (*channel * scale).clamp(0.0, 1.0);
+            }
+        }
+        if linear_light {
+            for channel in &mut pixel.0[..3] {
+                *channel = linear_to_srgb(*channel);
+            }
+        }
+    }
+
+    with_color_type(DynamicImage::ImageRgba32F(resized), image.color())
+}
+
+/// Linear-light RGBA copy of an image; alpha stays as it is
+fn to_linear(image: &DynamicImage) -> Rgba32FImage {
+    if image.color().bytes_per_pixel() / image.color().channel_count() == 1 {
+        // 8-bit inputs only have 256 codes to decode
+        let table: Vec<f32> = (0..=255u8).map(|code| srgb_to_linear(f32::from(code) / 255.0)).collect();
+        let rgba = image.to_rgba8();
+        return Rgba32FImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+            let [r, g, b, a] = rgba.get_pixel(x, y).0;
+            image::Rgba([
+                table[usize::from(r)],
+                table[usize::from(g)],
+                table[usize::from(b)],
+                f32::from(a) / 255.0,
+            ])
+        });
+    }
+
+    let mut linear = image.to_rgba32f();
+    for pixel in linear.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel = srgb_to_linear(*channel);
+        }
+    }
+    linear
+}
+
+/// Convert back to the color type the image came in
+pub(crate) fn with_color_type(image: DynamicImage, color: ColorType) -> DynamicImage {
+    match color {
+        ColorType::L8 => DynamicImage::ImageLuma8(image.to_luma8()),
+        ColorType::La8 => DynamicImage::ImageLumaA8(image.to_luma_alpha8()),
+        ColorType::Rgb8 => DynamicImage::ImageRgb8(image.to_rgb8()),
+        ColorType::Rgba8 => DynamicImage::ImageRgba8(image.to_rgba8()),
+        ColorType::L16 => DynamicImage::ImageLuma16(image.to_luma16()),
+        ColorType::La16 => DynamicImage::ImageLumaA16(image.to_luma_alpha16()),
+        ColorType::Rgb16 => DynamicImage::ImageRgb16(image.to_rgb16()),
+        ColorType::Rgba16 => DynamicImage::ImageRgba16(image.to_rgba16()),
+        ColorType::Rgb32F => DynamicImage::ImageRgb32F(image.to_rgb32f()),
+        _ => image,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgb, RgbImage};
+
+    #[test]
+    fn test_transfer_round_trip() {
+        for code in 0..=255u8 {
+            let value = f32::from(code) / 255.0;
+            let back = (linear_to_srgb(srgb_to_linear(value)) * 255.0).round() as u8;
+            assert_eq!(back, code);
+        }
+        assert_eq!(linear_to_srgb(-0.2), 0.0);
+        assert!((linear_to_srgb(1.3) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_resample_linear() {
+        // Alternating black and white columns average to half the light
+        let stripes = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 8, |x, _| {
+            if x % 2 == 0 { Rgb([0, 0, 0]) } else { Rgb([255, 255, 255]) }
+        }));
+        let filter = image::imageops::FilterType::Triangle;
+
+        let gamma = stripes.resize_exact(1, 1, filter).to_rgb8();
+        let linear = resample(&stripes, 1, 1, filter, true);
+        assert_eq!(linear.color(), ColorType::Rgb8);
+        let [value, ..] = linear.to_rgb8().get_pixel(0, 0).0;
+        assert!(gamma.get_pixel(0, 0).0[0] < 135);
+        assert!((184..=192).contains(&value), "linear average {}", value);
+
+        // Flat areas are untouched
+        let gray = DynamicImage::ImageLuma8(GrayImage::from_pixel(6, 6, Luma([77])));
+        let resized = resample(&gray, 3, 3, image::imageops::FilterType::Lanczos3, true);
+        assert_eq!(resized.as_luma8().unwrap().get_pixel(1, 1).0, [77]);
+    }
+}
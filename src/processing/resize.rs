@@ -1,34 +1,36 @@
 //! Image resizing algorithms and utilities
 
 use image::DynamicImage;
-use crate::config::ResizeMode;
+use crate::config::{Background, CropStrategy, FilterType, ResizeConfig, ResizeMode, SharpenOptions, UpscalePolicy};
 use crate::error::{Result, FastResizeError};
+use super::calculate_dimensions;
+use super::crop::attention_focus;
+use super::resample::resample;
+use super::sharpen::unsharp_mask;
 use tracing::debug;
 
 /// High-quality image resizer with various algorithms
 pub struct ImageResizer {
     filter: FilterType,
     preserve_aspect_ratio: bool,
+    linear_light: bool,
+    upscale: UpscalePolicy,
+    sharpen: Option<SharpenOptions>,
 }
 
-/// Available resize filters
-#[derive(Debug, Clone, Copy)]
-pub enum FilterType {
-    /// Nearest neighbor (fastest, lowest quality)
-    Nearest,
-    /// Triangle (linear interpolation)
-    Triangle,
-    /// Catmull-Rom cubic spline
-    CatmullRom,
-    /// Gaussian blur
-    Gaussian,
-    /// Lanczos with radius 3 (high quality, recommended)
-    Lanczos3,
-}
-
-impl Default for FilterType {
-    fn default() -> Self {
-        Self::Lanczos3
+impl FilterType {
+    /// Resolve `Auto` to a concrete filter for the given resize
+    pub fn resolve(
+        self,
+        original_width: u32,
+        original_height: u32,
+        target_width: u32,
+        target_height: u32,
+    ) -> FilterType {
+        match self {
+            Self::Auto => utils::suggest_filter(original_width, original_height, target_width, target_height),
+            filter => filter,
+        }
     }
 }
 
@@ -39,7 +41,8 @@ impl From<FilterType> for image::imageops::FilterType {
             FilterType::Triangle => image::imageops::FilterType::Triangle,
             FilterType::CatmullRom => image::imageops::FilterType::CatmullRom,
             FilterType::Gaussian => image::imageops::FilterType::Gaussian,
-            FilterType::Lanczos3 => image::imageops::FilterType::Lanczos3,
+            // Unresolved auto falls back to the default filter
+            FilterType::Lanczos3 | FilterType::Auto => image::imageops::FilterType::Lanczos3,
         }
     }
 }
@@ -50,6 +53,9 @@ impl ImageResizer {
         Self {
             filter: FilterType::Lanczos3,
             preserve_aspect_ratio: true,
+            linear_light: false,
+            upscale: UpscalePolicy::Always,
+            sharpen: None,
         }
     }
 
@@ -58,15 +64,44 @@ impl ImageResizer {
         Self {
             filter,
             preserve_aspect_ratio: true,
+            linear_light: false,
+            upscale: UpscalePolicy::Always,
+            sharpen: None,
         }
     }
 
+    /// Create a resizer with the filter, resampling and sharpening options of a configuration
+    pub fn for_config(config: &ResizeConfig) -> Self {
+        Self::with_filter(config.filter)
+            .linear_light(config.linear_light)
+            .upscale(config.upscale)
+            .sharpen(config.sharpen)
+    }
+
     /// Set whether to preserve aspect ratio
     pub fn preserve_aspect_ratio(mut self, preserve: bool) -> Self {
         self.preserve_aspect_ratio = preserve;
         self
     }
 
+    /// Set whether to resample in linear light instead of on gamma-encoded values
+    pub fn linear_light(mut self, linear_light: bool) -> Self {
+        self.linear_light = linear_light;
+        self
+    }
+
+    /// Set how far images may be enlarged
+    pub fn upscale(mut self, upscale: UpscalePolicy) -> Self {
+        self.upscale = upscale;
+        self
+    }
+
+    /// Set the unsharp mask applied to resized images
+    pub fn sharpen(mut self, sharpen: Option<SharpenOptions>) -> Self {
+        self.sharpen = sharpen;
+        self
+    }
+
     /// Resize an image according to the specified mode
     pub fn resize(&self, image: &DynamicImage, mode: &ResizeMode) -> Result<DynamicImage> {
         let (target_width, target_height) = self.calculate_target_dimensions(image, mode)?;
@@ -86,21 +121,61 @@ impl ImageResizer {
         }
 
         let resized = match mode {
-            ResizeMode::Fill { .. } => {
+            ResizeMode::Fill { anchor, crop, .. } => {
                 // For fill mode, we need to crop to maintain aspect ratio
-                self.resize_and_crop(image, target_width, target_height)?
+                let focus_point = match crop {
+                    CropStrategy::Focus { x, y } => (*x, *y),
+                    CropStrategy::Attention => attention_focus(image, target_width, target_height),
+                    // Sidecars are resolved by the engine, which knows the input path
+                    CropStrategy::Anchor | CropStrategy::Sidecar => anchor.focus_point(),
+                };
+                let cropped = self.resize_and_crop(image, target_width, target_height, focus_point)?;
+                self.sharpen_resized(image, cropped)
+            }
+            ResizeMode::Pad { width, height, background, anchor } => {
+                let fit = ResizeMode::Fit { width: *width, height: *height };
+                let (fit_width, fit_height) = limit_upscale(
+                    self.upscale,
+                    (image.width(), image.height()),
+                    calculate_dimensions(image.width(), image.height(), &fit)?,
+                    true,
+                );
+                let fitted = if (fit_width, fit_height) == (image.width(), image.height()) {
+                    image.clone()
+                } else {
+                    let filter = self.image_filter(image, fit_width, fit_height);
+                    self.sharpen_resized(image, resample(image, fit_width, fit_height, filter, self.linear_light))
+                };
+                pad(&fitted, target_width, target_height, *background, anchor.focus_point())
             }
             _ => {
                 // Standard resize maintaining aspect ratio
-                let filter: image::imageops::FilterType = self.filter.into();
-                image.resize(target_width, target_height, filter)
+                let filter = self.image_filter(image, target_width, target_height);
+                let resized = if self.linear_light || image.color().has_alpha() {
+                    resample(image, target_width, target_height, filter, self.linear_light)
+                } else {
+                    image.resize(target_width, target_height, filter)
+                };
+                self.sharpen_resized(image, resized)
             }
         };
 
         Ok(resized)
     }
 
-    /// Calculate target dimensions based on resize mode
+    /// Concrete image filter for resizing `image` to the target size
+    fn image_filter(
+        &self,
+        image: &DynamicImage,
+        target_width: u32,
+        target_height: u32,
+    ) -> image::imageops::FilterType {
+        self.filter
+            .resolve(image.width(), image.height(), target_width, target_height)
+            .into()
+    }
+
+    /// Calculate target dimensions based on resize mode and upscale policy
     fn calculate_target_dimensions(
         &self,
         image: &DynamicImage,
@@ -109,86 +184,46 @@ impl ImageResizer {
         let original_width = image.width();
         let original_height = image.height();
 
-        match mode {
-            ResizeMode::Scale { factor } => {
-                if *factor <= 0.0 {
-                    return Err(FastResizeError::invalid_parameters(
-                        "Scale factor must be positive"
-                    ));
-                }
-                let width = (original_width as f32 * factor).round() as u32;
-                let height = (original_height as f32 * factor).round() as u32;
-                Ok((width.max(1), height.max(1)))
-            }
-
-            ResizeMode::Width { width } => {
+        let target = match mode {
+            ResizeMode::Width { width } if !self.preserve_aspect_ratio => {
                 if *width == 0 {
                     return Err(FastResizeError::invalid_parameters(
                         "Width must be greater than 0"
                     ));
                 }
-                if self.preserve_aspect_ratio {
-                    let aspect_ratio = original_height as f32 / original_width as f32;
-                    let height = (*width as f32 * aspect_ratio).round() as u32;
-                    Ok((*width, height.max(1)))
-                } else {
-                    Ok((*width, original_height))
-                }
+                Ok((*width, original_height))
             }
 
-            ResizeMode::Height { height } => {
+            ResizeMode::Height { height } if !self.preserve_aspect_ratio => {
                 if *height == 0 {
                     return Err(FastResizeError::invalid_parameters(
                         "Height must be greater than 0"
                     ));
                 }
-                if self.preserve_aspect_ratio {
-                    let aspect_ratio = original_width as f32 / original_height as f32;
-                    let width = (*height as f32 * aspect_ratio).round() as u32;
-                    Ok((width.max(1), *height))
-                } else {
-                    Ok((original_width, *height))
-                }
+                Ok((original_width, *height))
             }
 
-            ResizeMode::Fit { width, height } => {
-                if *width == 0 || *height == 0 {
-                    return Err(FastResizeError::invalid_parameters(
-                        "Width and height must be greater than 0"
-                    ));
-                }
+            _ => calculate_dimensions(original_width, original_height, mode),
+        }?;
 
-                let original_aspect = original_width as f32 / original_height as f32;
-                let target_aspect = *width as f32 / *height as f32;
-
-                if original_aspect > target_aspect {
-                    // Image is wider, fit to width
-                    let new_height = (*width as f32 / original_aspect).round() as u32;
-                    Ok((*width, new_height.max(1)))
-                } else {
-                    // Image is taller, fit to height
-                    let new_width = (*height as f32 * original_aspect).round() as u32;
-                    Ok((new_width.max(1), *height))
-                }
-            }
-
-            ResizeMode::Fill { width, height } => {
-                if *width == 0 || *height == 0 {
-                    return Err(FastResizeError::invalid_parameters(
-                        "Width and height must be greater than 0"
-                    ));
-                }
-                Ok((*width, *height))
-            }
+        // Pad keeps its canvas; only the image placed on it is limited
+        if matches!(mode, ResizeMode::Pad { .. }) {
+            return Ok(target);
         }
+        let keeps_aspect = self.preserve_aspect_ratio && !matches!(mode, ResizeMode::Fill { .. });
+        Ok(limit_upscale(self.upscale, (original_width, original_height), target, keeps_aspect))
     }
 
     /// Resize and crop to fill target dimensions exactly
+    ///
+    /// The crop window is centered on the focus point, given as fractions
+    /// of width and height (0.0-1.0), and clamped to the image bounds.
     fn resize_and_crop(
         &self,
         image: &DynamicImage,
         target_width: u32,
         target_height: u32,
+        focus_point: (f32, f32),
     ) -> Result<DynamicImage> {
         let original_width = image.width();
         let original_height = image.height();
@@ -196,34 +231,27 @@ impl ImageResizer {
         let original_aspect = original_width as f32 / original_height as f32;
         let target_aspect = target_width as f32 / target_height as f32;
 
+        // Scale so the image covers the target, never below the target size
         let (intermediate_width, intermediate_height) = if original_aspect > target_aspect {
             // Image is wider, scale to height and crop width
             let scale_factor = target_height as f32 / original_height as f32;
             let new_width = (original_width as f32 * scale_factor).round() as u32;
-            (new_width, target_height)
+            (new_width.max(target_width), target_height)
         } else {
             // Image is taller, scale to width and crop height
             let scale_factor = target_width as f32 / original_width as f32;
             let new_height = (original_height as f32 * scale_factor).round() as u32;
-            (target_width, new_height)
+            (target_width, new_height.max(target_height))
         };
 
         // First resize to intermediate size
-        let filter: image::imageops::FilterType = self.filter.into();
-        let resized = image.resize_exact(intermediate_width, intermediate_height, filter);
-
-        // Then crop to exact target size
-        let crop_x = if intermediate_width > target_width {
-            (intermediate_width - target_width) / 2
-        } else {
-            0
-        };
+        let filter = self.image_filter(image, intermediate_width, intermediate_height);
+        let resized = resample(image, intermediate_width, intermediate_height, filter, self.linear_light);
 
-        let crop_y = if intermediate_height > target_height {
-            (intermediate_height - target_height) / 2
-        } else {
-            0
-        };
+        // Then crop to exact target size around the focus point
+        let (focus_x, focus_y) = focus_point;
+        let crop_x = crop_offset(focus_x, intermediate_width, target_width);
+        let crop_y = crop_offset(focus_y, intermediate_height, target_height);
 
         let cropped = resized.crop_imm(crop_x, crop_y, target_width, target_height);
         Ok(cropped)
@@ -237,51 +265,41 @@ impl ImageResizer {
         target_height: u32,
         focus_point: Option<(f32, f32)>, // (x, y) as percentages (0.0-1.0)
     ) -> Result<DynamicImage> {
-        let original_width = image.width();
-        let original_height = image.height();
-
-        let original_aspect = original_width as f32 / original_height as f32;
-        let target_aspect = target_width as f32 / target_height as f32;
-
-        // Calculate intermediate size (larger of the two dimensions to avoid upscaling)
-        let (intermediate_width, intermediate_height) = if original_aspect > target_aspect {
-            let scale_factor = target_height as f32 / original_height as f32;
-            let new_width = (original_width as f32 * scale_factor).round() as u32;
-            (new_width, target_height)
-        } else {
-            let scale_factor = target_width as f32 / original_width as f32;
-            let new_height = (original_height as f32 * scale_factor).round() as u32;
-            (target_width, new_height)
-        };
-
-        // Resize to intermediate size
-        let filter: image::imageops::FilterType = self.filter.into();
-        let resized = image.resize_exact(intermediate_width, intermediate_height, filter);
-
-        // Calculate crop position based on focus point
-        let (focus_x, focus_y) = focus_point.unwrap_or((0.5, 0.5)); // Default to center
+        if target_width == 0 || target_height == 0 {
+            return Err(FastResizeError::invalid_parameters(
+                "Width and height must be greater than 0"
+            ));
+        }
 
-        let crop_x = if intermediate_width > target_width {
-            let max_crop_x = intermediate_width - target_width;
-            let ideal_crop_x = (focus_x * intermediate_width as f32 - target_width as f32 / 2.0).round() as u32;
-            ideal_crop_x.min(max_crop_x).max(0)
-        } else {
-            0
-        };
+        // Default to center
+        let focus_point = focus_point.unwrap_or((0.5, 0.5));
+        self.resize_and_crop(image, target_width, target_height, focus_point)
+    }
 
-        let crop_y = if intermediate_height > target_height {
-            let max_crop_y = intermediate_height - target_height;
-            let ideal_crop_y = (focus_y * intermediate_height as f32 - target_height as f32 / 2.0).round() as u32;
-            ideal_crop_y.min(max_crop_y).max(0)
-        } else {
-            0
+    /// Apply the configured unsharp mask to an image resized from `original`
+    ///
+    /// An `auto` amount follows the downscale factor on the less shrunk axis,
+    /// which is the scale the image was resampled at when fill crops.
+    fn sharpen_resized(&self, original: &DynamicImage, resized: DynamicImage) -> DynamicImage {
+        let Some(sharpen) = self.sharpen else {
+            return resized;
         };
+        let downscale = (original.width() as f32 / resized.width() as f32)
+            .min(original.height() as f32 / resized.height() as f32);
+        let amount = sharpen.amount.resolve(downscale);
+        if amount <= 0.0 {
+            return resized;
+        }
 
-        let cropped = resized.crop_imm(crop_x, crop_y, target_width, target_height);
-        Ok(cropped)
+        debug!("Sharpening by {:.2} after a {:.1}x downscale", amount, downscale);
+        unsharp_mask(&resized, amount, sharpen.radius, sharpen.threshold)
     }
 
     /// Apply unsharp mask filter to enhance details after resizing
+    ///
+    /// Each color channel moves `amount` times further from a Gaussian blur
+    /// of `radius` pixels, where it differs by at least `threshold` levels.
+    /// Alpha is left unchanged and does not bleed into the color.
     pub fn apply_unsharp_mask(
         &self,
         image: &DynamicImage,
@@ -289,18 +307,14 @@ impl ImageResizer {
         radius: f32,
         threshold: u8,
     ) -> Result<DynamicImage> {
-        // This is a simplified unsharp mask implementation
-        // In a production environment, you might want to use imageproc or similar
-        if amount <= 0.0 || radius <= 0.0 {
+        if !(amount.is_finite() && radius.is_finite() && amount > 0.0 && radius > 0.0) {
             return Ok(image.clone());
         }
 
         debug!("Applying unsharp mask: amount={}, radius={}, threshold={}", 
                amount, radius, threshold);
 
-        // For now, return original image
-        // TODO: Implement proper unsharp mask using imageproc or custom implementation
-        Ok(image.clone())
+        Ok(unsharp_mask(image, amount, radius, threshold))
     }
 
     /// Optimize image for web delivery
@@ -316,6 +330,66 @@ impl Default for ImageResizer {
     }
 }
 
+
+/// Shrink target dimensions that would enlarge an image past the policy's factor
+///
+/// Targets with the image's aspect ratio become the original size times the
+/// factor; other targets (fill crops, stretches) shrink evenly, keeping their
+/// own aspect ratio.
+fn limit_upscale(
+    policy: UpscalePolicy,
+    (original_width, original_height): (u32, u32),
+    (target_width, target_height): (u32, u32),
+    keeps_aspect: bool,
+) -> (u32, u32) {
+    let Some(max_factor) = policy.max_factor() else {
+        return (target_width, target_height);
+    };
+    let scale = (target_width as f32 / original_width as f32)
+        .max(target_height as f32 / original_height as f32);
+    if scale <= max_factor {
+        return (target_width, target_height);
+    }
+
+    let (width, height) = if keeps_aspect {
+        (original_width as f32 * max_factor, original_height as f32 * max_factor)
+    } else {
+        let shrink = max_factor / scale;
+        (target_width as f32 * shrink, target_height as f32 * shrink)
+    };
+    ((width.round() as u32).max(1), (height.round() as u32).max(1))
+}
+
+/// Place an image on a `width`x`height` canvas of the background color
+///
+/// The position is given as fractions of the free space (0.0-1.0), so an
+/// anchor's focus point puts the image against the matching edges.
+fn pad(
+    image: &DynamicImage,
+    width: u32,
+    height: u32,
+    background: Background,
+    position: (f32, f32),
+) -> DynamicImage {
+    let mut canvas = image::RgbaImage::from_pixel(width, height, image::Rgba(background.rgba()));
+    let x = (width.saturating_sub(image.width()) as f32 * position.0.clamp(0.0, 1.0)).round();
+    let y = (height.saturating_sub(image.height()) as f32 * position.1.clamp(0.0, 1.0)).round();
+    image::imageops::overlay(&mut canvas, &image.to_rgba8(), x as i64, y as i64);
+
+    match background {
+        Background::Transparent => DynamicImage::ImageRgba8(canvas),
+        // Nothing shows through an opaque background
+        Background::Color(_) => DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8()),
+    }
+}
+
+/// Offset of a crop window of `target` pixels centered on `focus` (0.0-1.0)
+fn crop_offset(focus: f32, available: u32, target: u32) -> u32 {
+    let max_offset = available.saturating_sub(target);
+    let ideal = focus.clamp(0.0, 1.0) * available as f32 - target as f32 / 2.0;
+    (ideal.round().max(0.0) as u32).min(max_offset)
+}
+
 /// Utility functions for common resize operations
 pub mod utils {
     use super::*;
@@ -327,10 +401,7 @@ pub mod utils {
         filter: Option<FilterType>,
     ) -> Result<DynamicImage> {
         let resizer = ImageResizer::with_filter(filter.unwrap_or(FilterType::Lanczos3));
-        let mode = ResizeMode::Fill { 
-            width: size, 
-            height: size 
-        };
+        let mode = ResizeMode::fill(size, size);
         resizer.resize(image, &mode)
     }
 
@@ -395,6 +466,7 @@ pub mod utils {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::CropAnchor;
     use image::{ImageBuffer, Rgb};
 
     fn create_test_image(width: u32, height: u32) -> DynamicImage {
@@ -453,7 +525,7 @@ mod tests {
         // Fill mode
         let (w, h) = resizer.calculate_target_dimensions(
             &image,
-            &ResizeMode::Fill { width: 600, height: 600 }
+            &ResizeMode::fill(600, 600)
         ).unwrap();
         assert_eq!((w, h), (600, 600));
     }
@@ -509,6 +581,68 @@ mod tests {
         assert_eq!(cropped.height(), 500);
     }
 
+    #[test]
+    fn test_fill_exact_dimensions() {
+        let resizer = ImageResizer::new();
+
+        for (width, height) in [(333, 101), (101, 333), (640, 480), (50, 50)] {
+            let image = create_test_image(width, height);
+            for (target_width, target_height) in [(64, 64), (100, 37), (37, 100), (800, 600)] {
+                let mode = ResizeMode::fill(target_width, target_height);
+                let resized = resizer.resize(&image, &mode).unwrap();
+                assert_eq!((resized.width(), resized.height()), (target_width, target_height));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_crop_anchor() {
+        // Left half red, right half blue
+        let image = DynamicImage::ImageRgb8(ImageBuffer::from_fn(200, 100, |x, _| {
+            if x < 100 { Rgb([255, 0, 0]) } else { Rgb([0, 0, 255]) }
+        }));
+        let resizer = ImageResizer::with_filter(FilterType::Nearest);
+
+        let fill = |anchor| ResizeMode::Fill { width: 50, height: 50, anchor, crop: CropStrategy::Anchor };
+
+        let left = resizer.resize(&image, &fill(CropAnchor::Left)).unwrap().to_rgb8();
+        assert!(left.pixels().all(|p| p.0 == [255, 0, 0]));
+
+        let right = resizer.resize(&image, &fill(CropAnchor::BottomRight)).unwrap().to_rgb8();
+        assert!(right.pixels().all(|p| p.0 == [0, 0, 255]));
+
+        let center = resizer.resize(&image, &fill(CropAnchor::Center)).unwrap().to_rgb8();
+        assert_eq!(center.get_pixel(0, 25).0, [255, 0, 0]);
+        assert_eq!(center.get_pixel(49, 25).0, [0, 0, 255]);
+    }
+
+    #[test]
+    fn test_fill_crop_strategies() {
+        // Left half red, right half blue with a striped detail block on the far right
+        let image = DynamicImage::ImageRgb8(ImageBuffer::from_fn(400, 100, |x, y| {
+            if x >= 320 && (y / 4) % 2 == 0 {
+                Rgb([255, 255, 255])
+            } else if x < 200 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([0, 0, 255])
+            }
+        }));
+        let resizer = ImageResizer::with_filter(FilterType::Nearest);
+        let fill = |crop| ResizeMode::Fill { width: 50, height: 50, anchor: CropAnchor::Center, crop };
+
+        let focused = resizer.resize(&image, &fill(CropStrategy::Focus { x: 0.1, y: 0.5 })).unwrap().to_rgb8();
+        assert!(focused.pixels().all(|p| p.0 == [255, 0, 0]));
+
+        let attention = resizer.resize(&image, &fill(CropStrategy::Attention)).unwrap().to_rgb8();
+        assert!(attention.pixels().any(|p| p.0 == [255, 255, 255]));
+        assert!(attention.pixels().all(|p| p.0 != [255, 0, 0]));
+
+        // Without an input path the sidecar strategy falls back to the anchor
+        let sidecar = resizer.resize(&image, &fill(CropStrategy::Sidecar)).unwrap().to_rgb8();
+        assert_eq!(sidecar.get_pixel(0, 25).0, [255, 0, 0]);
+    }
+
     #[test]
     fn test_utility_functions() {
         let image = create_test_image(1000, 800);
@@ -553,4 +687,141 @@ mod tests {
             let _: image::imageops::FilterType = (*filter).into();
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_auto_filter_resolution() {
+        assert_eq!(FilterType::Auto.resolve(4000, 3000, 2000, 1500), FilterType::Lanczos3);
+        assert_eq!(FilterType::Auto.resolve(4000, 3000, 1200, 900), FilterType::CatmullRom);
+        assert_eq!(FilterType::Auto.resolve(4000, 3000, 400, 300), FilterType::Triangle);
+        assert_eq!(FilterType::Nearest.resolve(4000, 3000, 400, 300), FilterType::Nearest);
+
+        let image = create_test_image(400, 300);
+        let resized = ImageResizer::with_filter(FilterType::Auto)
+            .resize(&image, &ResizeMode::Width { width: 40 })
+            .unwrap();
+        assert_eq!((resized.width(), resized.height()), (40, 30));
+    }
+
+    #[test]
+    fn test_transparent_edges_have_no_fringes() {
+        // Opaque red logo on fully transparent green, a worst case for
+        // straight-alpha filtering
+        let logo = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(64, 48, |x, y| {
+            if (16..48).contains(&x) && (12..36).contains(&y) {
+                image::Rgba([255, 0, 0, 255])
+            } else {
+                image::Rgba([0, 255, 0, 0])
+            }
+        }));
+
+        let modes = [ResizeMode::Width { width: 20 }, ResizeMode::fill(24, 24)];
+        for linear_light in [false, true] {
+            for mode in &modes {
+                let resizer = ImageResizer::new().linear_light(linear_light);
+                let resized = resizer.resize(&logo, mode).unwrap();
+                assert_eq!(resized.color(), image::ColorType::Rgba8);
+
+                let pixels = resized.to_rgba8();
+                assert!(pixels.pixels().any(|p| p.0[3] > 0 && p.0[3] < 255), "edge should blend");
+                for pixel in pixels.pixels().filter(|p| p.0[3] > 0) {
+                    assert_eq!(pixel.0[..3], [255, 0, 0], "fringe {:?} in {:?}", pixel, mode);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pad_to_exact_canvas() {
+        let resizer = ImageResizer::new();
+        let image = create_test_image(400, 200);
+
+        let padded = resizer.resize(&image, &ResizeMode::pad(100, 100, Background::WHITE)).unwrap();
+        assert_eq!((padded.width(), padded.height()), (100, 100));
+        assert_eq!(padded.color(), image::ColorType::Rgb8);
+        let pixels = padded.to_rgb8();
+        // 100x50 image centered between 25px white bars
+        assert_eq!(pixels.get_pixel(50, 24).0, [255, 255, 255]);
+        assert_eq!(pixels.get_pixel(50, 75).0, [255, 255, 255]);
+        assert_ne!(pixels.get_pixel(50, 25).0, [255, 255, 255]);
+
+        let top = ResizeMode::Pad { width: 100, height: 100, background: Background::Transparent, anchor: CropAnchor::Top };
+        let padded = resizer.resize(&image, &top).unwrap().to_rgba8();
+        assert_eq!(padded.get_pixel(50, 0).0[3], 255);
+        assert_eq!(padded.get_pixel(50, 50).0, [0, 0, 0, 0]);
+
+        // Small images are enlarged to fit, like in fit mode
+        let padded = resizer.resize(&create_test_image(10, 20), &ResizeMode::pad(60, 60, Background::WHITE)).unwrap();
+        assert_eq!(padded.to_rgb8().get_pixel(14, 30).0, [255, 255, 255]);
+        assert_ne!(padded.to_rgb8().get_pixel(30, 30).0, [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_upscale_policy() {
+        let icon = create_test_image(400, 300);
+        let size = |policy, mode: &ResizeMode| {
+            let resized = ImageResizer::new().upscale(policy).resize(&icon, mode).unwrap();
+            (resized.width(), resized.height())
+        };
+
+        let width = ResizeMode::Width { width: 1920 };
+        assert_eq!(size(UpscalePolicy::Always, &width), (1920, 1440));
+        assert_eq!(size(UpscalePolicy::Never, &width), (400, 300));
+        assert_eq!(size(UpscalePolicy::UpToFactor(2.0), &width), (800, 600));
+        // Shrinking is never limited
+        assert_eq!(size(UpscalePolicy::Never, &ResizeMode::Width { width: 200 }), (200, 150));
+
+        // Fill keeps the requested aspect ratio at the largest allowed size
+        assert_eq!(size(UpscalePolicy::Never, &ResizeMode::fill(1080, 1080)), (300, 300));
+        assert_eq!(size(UpscalePolicy::Never, &ResizeMode::fill(200, 100)), (200, 100));
+
+        // Pad keeps the canvas and centers the unenlarged image on it
+        let padded = ImageResizer::new()
+            .upscale(UpscalePolicy::Never)
+            .resize(&icon, &ResizeMode::pad(1000, 1000, Background::WHITE))
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(padded.dimensions(), (1000, 1000));
+        assert_eq!(padded.get_pixel(299, 500).0, [255, 255, 255]);
+        assert_ne!(padded.get_pixel(300, 500).0, [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_sharpen_after_downscale() {
+        use crate::config::{SharpenAmount, SharpenOptions};
+
+        // Blocks of 8 pixels become 2-pixel blocks, blurred by the filter
+        let blocks = DynamicImage::ImageRgb8(ImageBuffer::from_fn(64, 64, |x, y| {
+            if (x / 8 + y / 8) % 2 == 0 { Rgb([60, 60, 60]) } else { Rgb([190, 190, 190]) }
+        }));
+        let contrast = |image: &DynamicImage| {
+            let pixels = image.to_luma8();
+            let (min, max) = pixels.pixels().fold((255, 0), |(min, max), p| (p.0[0].min(min), p.0[0].max(max)));
+            i32::from(max) - i32::from(min)
+        };
+        let mode = ResizeMode::Width { width: 16 };
+
+        let soft = ImageResizer::with_filter(FilterType::Triangle).resize(&blocks, &mode).unwrap();
+        let sharpened = ImageResizer::with_filter(FilterType::Triangle)
+            .sharpen(Some(SharpenOptions::default()))
+            .resize(&blocks, &mode)
+            .unwrap();
+        assert_eq!((sharpened.width(), sharpened.height()), (16, 16));
+        assert!(contrast(&sharpened) > contrast(&soft));
+
+        // Auto does nothing without a downscale; a fixed amount still applies
+        assert_eq!(SharpenAmount::Auto.resolve(1.0), 0.0);
+        assert_eq!(SharpenAmount::Auto.resolve(4.0), 0.5);
+        assert_eq!(SharpenAmount::Auto.resolve(100.0), 1.0);
+        let upscaled = |sharpen| {
+            ImageResizer::new().sharpen(Some(sharpen)).resize(&soft, &ResizeMode::Width { width: 32 }).unwrap()
+        };
+        let plain = ImageResizer::new().resize(&soft, &ResizeMode::Width { width: 32 }).unwrap();
+        assert_eq!(upscaled(SharpenOptions::default()).to_rgb8(), plain.to_rgb8());
+        let fixed = SharpenOptions { amount: SharpenAmount::Fixed(1.0), ..SharpenOptions::default() };
+        assert_ne!(upscaled(fixed).to_rgb8(), plain.to_rgb8());
+
+        // The web preset sharpens too
+        let web = ImageResizer::new().optimize_for_web(&soft).unwrap();
+        assert!(contrast(&web) > contrast(&soft));
+    }
+}
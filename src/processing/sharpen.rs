@@ -0,0 +1,95 @@
+//! Unsharp mask sharpening
+//!
+//! Each color channel is pushed away from a Gaussian-blurred copy of
+//! itself. Images with alpha are blurred with alpha-weighted color, so
+//! transparent pixels neither darken nor tint the visible edges next to
+//! them, and the alpha channel itself is left alone.
+
+use image::DynamicImage;
+
+use super::resample::with_color_type;
+
+/// Sharpen `image` by `amount` times its difference from a blur of `radius`
+///
+/// Differences smaller than `threshold` (in 0-255 levels) are left alone,
+/// which keeps flat areas and noise from being amplified. The result keeps
+/// the color type of the input.
+pub fn unsharp_mask(image: &DynamicImage, amount: f32, radius: f32, threshold: u8) -> DynamicImage {
+    let has_alpha = image.color().has_alpha();
+    let mut pixels = image.to_rgba32f();
+
+    let blurred = if has_alpha {
+        let mut premultiplied = pixels.clone();
+        for pixel in premultiplied.pixels_mut() {
+            let alpha = pixel.0[3];
+            for channel in &mut pixel.0[..3] {
+                *channel *= alpha;
+            }
+        }
+        let mut blurred = image::imageops::blur(&premultiplied, radius);
+        for pixel in blurred.pixels_mut() {
+            let alpha = pixel.0[3];
+            let scale = if alpha > 0.0 { 1.0 / alpha } else { 0.0 };
+            for channel in &mut pixel.0[..3] {
+                *channel *= scale;
+            }
+        }
+        blurred
+    } else {
+        image::imageops::blur(&pixels, radius)
+    };
+    let threshold = f32::from(threshold) / 255.0;
+
+    for (pixel, blurred) in pixels.pixels_mut().zip(blurred.pixels()) {
+        for (channel, &blurred) in pixel.0[..3].iter_mut().zip(&blurred.0[..3]) {
+            let detail = *channel - blurred;
+            if detail.abs() >= threshold {
+                *channel = (*channel + amount * detail).clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    with_color_type(DynamicImage::ImageRgba32F(pixels), image.color())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ColorType, Rgba, RgbaImage, Luma, GrayImage};
+
+    /// Dark left half, light right half
+    fn edge(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, _| if x < width / 2 { Luma([80]) } else { Luma([170]) })
+    }
+
+    #[test]
+    fn test_unsharp_mask_raises_edge_contrast() {
+        let image = DynamicImage::ImageLuma8(edge(16, 4));
+        let sharpened = unsharp_mask(&image, 1.0, 1.0, 0);
+        assert_eq!(sharpened.color(), ColorType::L8);
+
+        let pixels = sharpened.to_luma8();
+        // Overshoot on both sides of the edge, flat areas untouched
+        assert!(pixels.get_pixel(7, 0).0[0] < 80);
+        assert!(pixels.get_pixel(8, 0).0[0] > 170);
+        assert_eq!(pixels.get_pixel(0, 0).0[0], 80);
+        assert_eq!(pixels.get_pixel(15, 0).0[0], 170);
+
+        // Edges below the threshold are left alone
+        let unchanged = unsharp_mask(&image, 1.0, 1.0, 100);
+        assert_eq!(unchanged.to_luma8(), image.to_luma8());
+    }
+
+    #[test]
+    fn test_unsharp_mask_ignores_transparent_color() {
+        // Opaque gray next to transparent pixels holding bright red
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(16, 4, |x, _| {
+            if x < 8 { Rgba([120, 120, 120, 255]) } else { Rgba([255, 0, 0, 0]) }
+        }));
+        let sharpened = unsharp_mask(&image, 2.0, 1.5, 0).to_rgba8();
+
+        // Transparent neighbors count for nothing, so there is no edge to sharpen
+        assert_eq!(sharpened.get_pixel(7, 0).0, [120, 120, 120, 255]);
+        assert_eq!(sharpened.get_pixel(12, 0).0[3], 0);
+    }
+}
@@ -0,0 +1,235 @@
+//! Structural similarity (SSIM and MS-SSIM)
+//!
+//! Scores compare luma only, with an 11x11 Gaussian window (sigma 1.5) and
+//! the usual constants K1 = 0.01, K2 = 0.03. Alpha is ignored.
+
+use image::DynamicImage;
+
+use crate::config::SimilarityTarget;
+use crate::error::Result;
+
+/// Gaussian window radius; the window is 11 pixels across
+const WINDOW_RADIUS: usize = 5;
+
+const WINDOW_SIGMA: f32 = 1.5;
+
+/// Stabilizing constants for luma in 0.0-1.0
+const C1: f32 = 0.01 * 0.01;
+const C2: f32 = 0.03 * 0.03;
+
+/// Per-scale exponents from Wang, Simoncelli and Bovik (2003)
+const MS_SSIM_WEIGHTS: [f64; 5] = [0.0448, 0.2856, 0.3001, 0.2363, 0.1333];
+
+/// Mean SSIM of two images of the same size, 1.0 when identical
+///
+/// # Panics
+///
+/// Panics if the images differ in size.
+pub fn ssim(a: &DynamicImage, b: &DynamicImage) -> f64 {
+    let (a, b) = planes(a, b);
+    compare(&a, &b).0
+}
+
+/// Multi-scale SSIM of two images of the same size, 1.0 when identical
+///
+/// Uses five scales, or fewer when the image gets smaller than the window
+/// first; the remaining weights are renormalized. Images under 22 pixels on
+/// a side score the same as with `ssim`.
+///
+/// # Panics
+///
+/// Panics if the images differ in size.
+pub fn ms_ssim(a: &DynamicImage, b: &DynamicImage) -> f64 {
+    let (mut a, mut b) = planes(a, b);
+
+    let window = 2 * WINDOW_RADIUS + 1;
+    let scales = 1 + (1..MS_SSIM_WEIGHTS.len())
+        .take_while(|&scale| a.width.min(a.height) >> scale >= window)
+        .count();
+    let weights = &MS_SSIM_WEIGHTS[..scales];
+    let total: f64 = weights.iter().sum();
+
+    let mut score = 1.0;
+    for (scale, weight) in weights.iter().enumerate() {
+        let (ssim, contrast_structure) = compare(&a, &b);
+        let value = if scale + 1 == scales { ssim } else { contrast_structure };
+        // Negative terms only occur for unrelated images; treat them as no similarity
+        score *= value.max(0.0).powf(weight / total);
+
+        if scale + 1 < scales {
+            a = a.downsample();
+            b = b.downsample();
+        }
+    }
+    score
+}
+
+/// Score an encoded output against the image it was encoded from, using
+/// the target's metric
+pub fn measure(target: SimilarityTarget, reference: &DynamicImage, encoded: &[u8]) -> Result<f64> {
+    let decoded = image::load_from_memory(encoded)?;
+    Ok(match target {
+        SimilarityTarget::Ssim(_) => ssim(reference, &decoded),
+        SimilarityTarget::MsSsim(_) => ms_ssim(reference, &decoded),
+    })
+}
+
+/// Single-channel floating point image
+struct Plane {
+    width: usize,
+    height: usize,
+    data: Vec<f32>,
+}
+
+impl Plane {
+    fn luma(image: &DynamicImage) -> Self {
+        let luma = image.to_luma32f();
+        Self {
+            width: luma.width() as usize,
+            height: luma.height() as usize,
+            data: luma.into_raw(),
+        }
+    }
+
+    fn zip(&self, other: &Self, f: impl Fn(f32, f32) -> f32) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().zip(&other.data).map(|(&a, &b)| f(a, b)).collect(),
+        }
+    }
+
+    /// Gaussian blur, repeating edge pixels
+    fn blur(&self, kernel: &[f32]) -> Self {
+        let radius = kernel.len() / 2;
+        let (width, height) = (self.width, self.height);
+        let tap = |center: usize, offset: usize, len: usize| (center + offset).saturating_sub(radius).min(len - 1);
+
+        let mut rows = vec![0.0; self.data.len()];
+        for y in 0..height {
+            let row = &self.data[y * width..(y + 1) * width];
+            for x in 0..width {
+                rows[y * width + x] = kernel.iter().enumerate()
+                    .map(|(i, k)| k * row[tap(x, i, width)])
+                    .sum();
+            }
+        }
+
+        let mut data = vec![0.0; self.data.len()];
+        for y in 0..height {
+            for x in 0..width {
+                data[y * width + x] = kernel.iter().enumerate()
+                    .map(|(i, k)| k * rows[tap(y, i, height) * width + x])
+                    .sum();
+            }
+        }
+        Self { width, height, data }
+    }
+
+    /// Halve both dimensions by averaging 2x2 blocks
+    fn downsample(&self) -> Self {
+        let (width, height) = (self.width / 2, self.height / 2);
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let at = |dx: usize, dy: usize| self.data[(2 * y + dy) * self.width + 2 * x + dx];
+                data.push((at(0, 0) + at(1, 0) + at(0, 1) + at(1, 1)) / 4.0);
+            }
+        }
+        Self { width, height, data }
+    }
+}
+
+fn planes(a: &DynamicImage, b: &DynamicImage) -> (Plane, Plane) {
+    assert_eq!(
+        (a.width(), a.height()),
+        (b.width(), b.height()),
+        "similarity needs images of the same size"
+    );
+    (Plane::luma(a), Plane::luma(b))
+}
+
+/// Mean SSIM and mean contrast-structure term over every pixel
+fn compare(a: &Plane, b: &Plane) -> (f64, f64) {
+    let kernel = gaussian_kernel();
+    let mean_a = a.blur(&kernel);
+    let mean_b = b.blur(&kernel);
+    let square_a = a.zip(a, |a, _| a * a).blur(&kernel);
+    let square_b = b.zip(b, |b, _| b * b).blur(&kernel);
+    let product = a.zip(b, |a, b| a * b).blur(&kernel);
+
+    let (mut ssim, mut contrast_structure) = (0.0, 0.0);
+    for i in 0..a.data.len() {
+        let (mu_a, mu_b) = (mean_a.data[i], mean_b.data[i]);
+        let variance_a = square_a.data[i] - mu_a * mu_a;
+        let variance_b = square_b.data[i] - mu_b * mu_b;
+        let covariance = product.data[i] - mu_a * mu_b;
+
+        let luminance = (2.0 * mu_a * mu_b + C1) / (mu_a * mu_a + mu_b * mu_b + C1);
+        let cs = (2.0 * covariance + C2) / (variance_a + variance_b + C2);
+        ssim += f64::from(luminance * cs);
+        contrast_structure += f64::from(cs);
+    }
+
+    let count = a.data.len().max(1) as f64;
+    (ssim / count, contrast_structure / count)
+}
+
+fn gaussian_kernel() -> Vec<f32> {
+    let weights: Vec<f32> = (0..=2 * WINDOW_RADIUS)
+        .map(|i| {
+            let d = i as f32 - WINDOW_RADIUS as f32;
+            (-d * d / (2.0 * WINDOW_SIGMA * WINDOW_SIGMA)).exp()
+        })
+        .collect();
+    let total: f32 = weights.iter().sum();
+    weights.into_iter().map(|w| w / total).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Luma, Rgb, RgbImage};
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(x * 3) as u8, (y * 3) as u8, ((x + y) * 2) as u8])
+        }))
+    }
+
+    fn with_noise(image: &DynamicImage, strength: i32) -> DynamicImage {
+        let mut noisy = image.to_rgb8();
+        for (x, y, pixel) in noisy.enumerate_pixels_mut() {
+            let n = (x.wrapping_mul(7919) ^ y.wrapping_mul(104_729)) % 97;
+            let offset = (n as i32 - 48) * strength / 48;
+            for c in pixel.0.iter_mut() {
+                *c = (i32::from(*c) + offset).clamp(0, 255) as u8;
+            }
+        }
+        DynamicImage::ImageRgb8(noisy)
+    }
+
+    #[test]
+    fn test_identical_images_score_one() {
+        let image = gradient(64, 48);
+        assert!((ssim(&image, &image) - 1.0).abs() < 1e-6);
+        assert!((ms_ssim(&image, &image) - 1.0).abs() < 1e-6);
+
+        // Below the window size, MS-SSIM falls back to one scale
+        let small = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(8, 8, Luma([90])));
+        assert!((ms_ssim(&small, &small) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_noise_lowers_score() {
+        let image = gradient(80, 80);
+        let light = with_noise(&image, 4);
+        let heavy = with_noise(&image, 40);
+
+        for metric in [ssim, ms_ssim] {
+            let (light, heavy) = (metric(&image, &light), metric(&image, &heavy));
+            assert!(light < 1.0 && heavy < light, "scores {} and {}", light, heavy);
+            assert!(heavy >= 0.0);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Encoding under a file size budget or down to a similarity target
+
+use std::borrow::Cow;
+
+use image::DynamicImage;
+use tracing::debug;
+
+use crate::config::{ImageFormat, ResizeConfig, SimilarityTarget};
+use crate::error::{FastResizeError, Result};
+use super::formats::{encode_image, get_size_multiplier};
+use super::metadata::Metadata;
+use super::resample::resample;
+use super::similarity::measure;
+
+/// Lowest quality tried before shrinking the image instead
+pub const MIN_TARGET_QUALITY: u8 = 30;
+
+/// Smallest width or height an image is shrunk to while fitting a budget
+const MIN_TARGET_EDGE: u32 = 16;
+
+/// Encoded output file
+#[derive(Debug, Clone)]
+pub struct EncodedOutput {
+    /// File contents, including metadata
+    pub data: Vec<u8>,
+    /// Quality the image was encoded at, for formats with a quality setting
+    pub quality: Option<u8>,
+    /// Score reached against the image it was encoded from, when a
+    /// similarity target was searched
+    pub similarity: Option<f64>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Whether the format's size follows the quality setting
+pub fn has_quality(format: ImageFormat, config: &ResizeConfig) -> bool {
+    match format {
+        ImageFormat::Jpeg | ImageFormat::Avif => true,
+        ImageFormat::WebP => !config.webp.lossless,
+        _ => false,
+    }
+}
+
+/// Encode an image with its metadata, keeping under `config.target_size`
+///
+/// With `config.min_similarity`, the starting quality is the lowest one
+/// whose decoded output reaches the score, instead of `config.quality`.
+/// Without a budget, or when the output already fits, the image is encoded
+/// once at the starting quality. Otherwise the highest quality down to
+/// `MIN_TARGET_QUALITY` that fits is found by binary search; if even that is
+/// too large, the image is shrunk and the search repeated. The budget wins
+/// over the similarity target.
+pub fn encode_output(
+    image: &DynamicImage,
+    format: ImageFormat,
+    config: &ResizeConfig,
+    metadata: &Metadata,
+) -> Result<EncodedOutput> {
+    let searchable = has_quality(format, config);
+    let encode = |image: &DynamicImage, quality: u8| -> Result<Vec<u8>> {
+        let config = ResizeConfig { quality, ..config.clone() };
+        metadata.embed(encode_image(image, format, &config)?, format)
+    };
+
+    // Scoring needs to decode the output, which AVIF cannot do here
+    let scored = config.min_similarity.filter(|_| searchable && format != ImageFormat::Avif);
+    if config.min_similarity.is_some() && scored.is_none() {
+        debug!("Ignoring the similarity target for {:?} output", format);
+    }
+    let output = |image: &DynamicImage, data: Vec<u8>, quality: u8| -> Result<EncodedOutput> {
+        let similarity = scored.map(|target| measure(target, image, &data)).transpose()?;
+        Ok(EncodedOutput {
+            data,
+            quality: searchable.then_some(quality),
+            similarity,
+            width: image.width(),
+            height: image.height(),
+        })
+    };
+
+    let (quality, mut data) = match scored {
+        Some(target) => search_similarity(image, target, &encode)?,
+        None => (config.quality, encode(image, config.quality)?),
+    };
+    let Some(max_bytes) = config.target_size else {
+        return output(image, data, quality);
+    };
+
+    let mut image = Cow::Borrowed(image);
+    loop {
+        let size = data.len() as u64;
+        if size <= max_bytes {
+            return output(&image, data, quality);
+        }
+
+        // Size at the lowest quality tried, which decides how far to shrink
+        let mut smallest = size;
+        if searchable && quality > MIN_TARGET_QUALITY {
+            let floor = encode(&image, MIN_TARGET_QUALITY)?;
+            smallest = floor.len() as u64;
+            if smallest <= max_bytes {
+                let (quality, data) = search_quality(&image, format, quality, size, max_bytes, floor, &encode)?;
+                debug!("Quality {} fits {} bytes at {}x{}", quality, max_bytes, image.width(), image.height());
+                return output(&image, data, quality);
+            }
+        }
+
+        // Area, and roughly file size, follow the square of the scale
+        let factor = ((max_bytes as f64 / smallest as f64).sqrt() * 0.95).min(0.9);
+        let width = (f64::from(image.width()) * factor).round() as u32;
+        let height = (f64::from(image.height()) * factor).round() as u32;
+        if width < MIN_TARGET_EDGE || height < MIN_TARGET_EDGE {
+            return Err(FastResizeError::invalid_parameters(format!(
+                "Cannot encode {:?} under {} bytes; smallest attempt was {} bytes",
+                format, max_bytes, smallest
+            )));
+        }
+
+        debug!("Shrinking to {}x{} to fit {} bytes", width, height, max_bytes);
+        let filter = config.filter.resolve(image.width(), image.height(), width, height).into();
+        image = Cow::Owned(resample(&image, width, height, filter, config.linear_light));
+        data = encode(&image, quality)?;
+    }
+}
+
+/// Lowest quality whose decoded output reaches the similarity target
+///
+/// Scores rise with quality, so the search bisects 1-100. When even quality
+/// 100 falls short, it is used anyway.
+fn search_similarity<F>(image: &DynamicImage, target: SimilarityTarget, encode: &F) -> Result<(u8, Vec<u8>)>
+where
+    F: Fn(&DynamicImage, u8) -> Result<Vec<u8>>,
+{
+    // `low` is known to fall short; `high` reaches the target, or is 100
+    let (mut low, mut high) = (0, 100);
+    let mut best = None;
+
+    while high - low > 1 {
+        let probe = low + (high - low) / 2;
+        let data = encode(image, probe)?;
+        if measure(target, image, &data)? >= target.min_score() {
+            high = probe;
+            best = Some(data);
+        } else {
+            low = probe;
+        }
+    }
+
+    let data = match best {
+        Some(data) => {
+            debug!("Quality {} reaches {:?}", high, target);
+            data
+        }
+        None => {
+            debug!("No quality reaches {:?}", target);
+            encode(image, high)?
+        }
+    };
+    Ok((high, data))
+}
+
+/// Highest quality whose output fits `max_bytes`
+///
+/// `too_large` is known not to fit and `floor` (at `MIN_TARGET_QUALITY`) is
+/// known to fit. The first probe comes from the `get_size_multiplier`
+/// estimates, scaled to the measured size; the rest bisect.
+fn search_quality<F>(
+    image: &DynamicImage,
+    format: ImageFormat,
+    too_large: u8,
+    too_large_size: u64,
+    max_bytes: u64,
+    floor: Vec<u8>,
+    encode: &F,
+) -> Result<(u8, Vec<u8>)>
+where
+    F: Fn(&DynamicImage, u8) -> Result<Vec<u8>>,
+{
+    let (mut low, mut high) = (MIN_TARGET_QUALITY, too_large);
+    let mut best = floor;
+
+    let estimate = |quality: u8| {
+        too_large_size as f32 * get_size_multiplier(format, quality) / get_size_multiplier(format, too_large)
+    };
+    let mut probe = (low + 1..high)
+        .rev()
+        .find(|&quality| estimate(quality) <= max_bytes as f32)
+        .unwrap_or(low + (high - low) / 2);
+
+    while high - low > 1 {
+        let data = encode(image, probe)?;
+        if data.len() as u64 <= max_bytes {
+            low = probe;
+            best = data;
+        } else {
+            high = probe;
+        }
+        probe = low + (high - low) / 2;
+    }
+
+    Ok((low, best))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    /// Noisy image that compresses poorly at high quality
+    fn detailed_image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let n = x.wrapping_mul(7919) ^ y.wrapping_mul(104_729) ^ (x * y);
+            Rgb([(n % 251) as u8, (n / 3 % 241) as u8, (n / 7 % 239) as u8])
+        }))
+    }
+
+    #[test]
+    fn test_quality_search_fits_budget() {
+        let image = detailed_image(256, 256);
+        let metadata = Metadata::default();
+        let config = ResizeConfig::new().quality(95);
+
+        let full = encode_output(&image, ImageFormat::Jpeg, &config, &metadata).unwrap();
+        assert_eq!(full.quality, Some(95));
+
+        let budget = full.data.len() as u64 * 2 / 3;
+        let fitted = encode_output(&image, ImageFormat::Jpeg, &config.clone().target_size(budget), &metadata).unwrap();
+        assert!(fitted.data.len() as u64 <= budget);
+        assert_eq!((fitted.width, fitted.height), (256, 256));
+        let quality = fitted.quality.unwrap();
+        assert!((MIN_TARGET_QUALITY..95).contains(&quality));
+
+        // The next quality up would not have fit
+        let above = encode_image(&image, ImageFormat::Jpeg, &config.clone().quality(quality + 1)).unwrap();
+        assert!(above.len() as u64 > budget);
+    }
+
+    #[test]
+    fn test_budget_falls_back_to_downscaling() {
+        let image = detailed_image(256, 256);
+        let metadata = Metadata::default();
+
+        let config = ResizeConfig::new().quality(90).target_size(4_000);
+        let fitted = encode_output(&image, ImageFormat::WebP, &config, &metadata).unwrap();
+        assert!(fitted.data.len() <= 4_000);
+        assert!(fitted.width < 256 && fitted.width == fitted.height);
+        assert_eq!(image::load_from_memory(&fitted.data).unwrap().width(), fitted.width);
+
+        // Formats without a quality setting can only shrink
+        let config = ResizeConfig::new().target_size(60_000);
+        let fitted = encode_output(&image, ImageFormat::Png, &config, &metadata).unwrap();
+        assert!(fitted.data.len() <= 60_000);
+        assert_eq!(fitted.quality, None);
+
+        let config = ResizeConfig::new().target_size(10);
+        assert!(encode_output(&image, ImageFormat::Jpeg, &config, &metadata).is_err());
+    }
+
+    #[test]
+    fn test_similarity_picks_lowest_quality() {
+        let image = detailed_image(128, 128);
+        let metadata = Metadata::default();
+        let target = SimilarityTarget::MsSsim(0.95);
+        let config = ResizeConfig::new().min_similarity(target);
+
+        let encoded = encode_output(&image, ImageFormat::Jpeg, &config, &metadata).unwrap();
+        let quality = encoded.quality.unwrap();
+        assert!(encoded.similarity.unwrap() >= 0.95);
+
+        // One quality lower falls short
+        let below = encode_image(&image, ImageFormat::Jpeg, &config.clone().quality(quality - 1)).unwrap();
+        assert!(measure(target, &image, &below).unwrap() < 0.95);
+
+        // A stricter target needs a higher quality
+        let stricter = config.clone().min_similarity(SimilarityTarget::MsSsim(0.99));
+        let encoded = encode_output(&image, ImageFormat::Jpeg, &stricter, &metadata).unwrap();
+        assert!(encoded.quality.unwrap() > quality);
+
+        // Lossless outputs are not scored
+        let encoded = encode_output(&image, ImageFormat::Png, &config, &metadata).unwrap();
+        assert_eq!((encoded.quality, encoded.similarity), (None, None));
+    }
+}
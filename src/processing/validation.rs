@@ -468,11 +468,11 @@ mod tests {
         let mut temp_files = Vec::new();
         for i in 0..3 {
             let mut temp_file = NamedTempFile::with_suffix(".jpg").unwrap();
-            temp_file.write_all(&format!("content {}", i).as_bytes()).unwrap();
+            temp_file.write_all(format!("content {}", i).as_bytes()).unwrap();
             temp_files.push(temp_file);
         }
         
-        let paths: Vec<_> = temp_files.iter().map(|f| f.path()).collect();
+        let paths: Vec<_> = temp_files.iter().map(|f| f.path().to_path_buf()).collect();
         let results = batch_validator.validate_batch(paths.clone()).await;
         
         assert_eq!(results.len(), 3);
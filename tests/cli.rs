@@ -0,0 +1,80 @@
+//! End-to-end tests of the fastresize binary
+
+use std::path::Path;
+
+use assert_cmd::Command;
+use tempfile::TempDir;
+
+/// Write a small solid-color PNG into `dir`
+fn write_png(dir: &Path, name: &str) {
+    image::RgbImage::from_pixel(64, 48, image::Rgb([200, 100, 50]))
+        .save(dir.join(name))
+        .unwrap();
+}
+
+#[test]
+fn test_resize_without_profile_keeps_filename() {
+    let input = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    write_png(input.path(), "a.png");
+
+    Command::cargo_bin("fastresize")
+        .unwrap()
+        .arg("-i")
+        .arg(input.path())
+        .arg("-o")
+        .arg(output.path())
+        .args(["-w", "32", "--quiet"])
+        .assert()
+        .success();
+
+    let resized = output.path().join("a.png");
+    assert!(resized.exists());
+    assert!(!output.path().join("a_resized.png").exists());
+    assert_eq!(image::open(resized).unwrap().width(), 32);
+}
+
+#[test]
+fn test_dry_run_json_lists_files_as_events() {
+    let input = TempDir::new().unwrap();
+    let output = TempDir::new().unwrap();
+    write_png(input.path(), "a.png");
+    write_png(input.path(), "b.png");
+
+    let assert = Command::cargo_bin("fastresize")
+        .unwrap()
+        .arg("-i")
+        .arg(input.path())
+        .arg("-o")
+        .arg(output.path())
+        .args(["-w", "32", "--dry-run", "--json"])
+        .assert()
+        .success();
+
+    // Every stdout line is a JSON event: one per file, then the summary
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events.len(), 3);
+    assert!(events[..2].iter().all(|event| event["event"] == "dry_run"));
+    assert!(events[..2].iter().any(|event| event["input_path"].as_str().unwrap().ends_with("a.png")));
+    assert_eq!(events[2]["event"], "summary");
+    assert_eq!(std::fs::read_dir(output.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_profiles_listing_describes_built_in_profiles() {
+    let assert = Command::cargo_bin("fastresize")
+        .unwrap()
+        .args(["profiles", "--detailed"])
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("fit 300x300, quality 80, WEBP"), "{}", stdout);
+    assert!(stdout.contains("768px wide, quality 75, WEBP"), "{}", stdout);
+    assert!(stdout.contains("fit 10x10in, quality 95, original format, 300 DPI"), "{}", stdout);
+    assert!(stdout.contains("archive"), "{}", stdout);
+}